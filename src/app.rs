@@ -1,4 +1,12 @@
-use crate::widget;
+use std::cmp::Reverse;
+use std::time::Instant;
+
+use chrono::{DateTime, Local};
+use tui::layout::Rect;
+
+use crate::config::Countdown;
+use crate::keymap::KeyChord;
+use crate::widget::{self, SortMode};
 
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub enum Mode {
@@ -9,12 +17,18 @@ pub enum Mode {
     EditItem,
     DisplayItem,
     DisplayHelp,
-}
-
-#[derive(PartialEq, Clone, Copy, Debug)]
-pub enum HelpMode {
-    ItemHelp,
-    WorkspaceHelp,
+    Confirm,
+    Search,
+    MoveItem,
+    Prompt,
+    CommandLine,
+    Palette,
+    Agenda,
+    Calendar,
+    Board,
+    Dashboard,
+    Archive,
+    Trash,
 }
 
 pub struct App {
@@ -28,21 +42,241 @@ pub struct App {
     pub add_item: widget::AddItemState,
     pub edit_item: widget::EditItemState,
     pub current_item: usize,
-    pub summary_scroll_state: SummaryScrollState,
+    pub item_scroll: ScrollState,
+    pub workspace_scroll: ScrollState,
+    pub count: Option<usize>,
+    pub pending_keys: Vec<KeyChord>,
+    pub item_areas: Vec<ItemArea>,
+    pub workspace_areas: Vec<(Rect, usize)>,
+    pub has_sidebar: bool,
     pub is_modified: bool,
-    pub help_mode: HelpMode,
-    pub help_item: widget::HelpItemWidget,
-    pub help_workspace: widget::HelpWorkspaceWidget,
+    pub modified_at: Option<Instant>,
+    pub message: Option<StatusMessage>,
+    pub help: widget::HelpState,
+    pub confirm: widget::ConfirmState,
+    pub search: widget::SearchState,
+    pub move_item: Option<(usize, Vec<usize>)>,
+    pub visual_anchor: Option<usize>,
+    pub prompt: widget::PromptState,
+    pub command_line: widget::CommandLineState,
+    pub palette: widget::PaletteState,
+    pub agenda: widget::AgendaState,
+    pub calendar: widget::CalendarState,
+    pub board: widget::BoardState,
+    pub show_details: bool,
+    pub show_snoozed: bool,
+    pub archived: Vec<widget::ArchivedItem>,
+    pub archive: widget::ArchiveState,
+    pub trashed_items: Vec<widget::TrashedItem>,
+    pub trashed_workspaces: Vec<widget::TrashedWorkspace>,
+    pub trash: widget::TrashState,
+    pub countdown: Countdown,
+    pub toasts: Vec<StatusMessage>,
+    pub reminders_checked_at: DateTime<Local>,
+    pub needs_clear: bool,
+}
+
+impl App {
+    pub fn set_modified(&mut self) {
+        self.is_modified = true;
+        self.modified_at = Some(Instant::now());
+    }
+
+    // Indices into `items[workspace]` in the order they are displayed.
+    pub fn visible_items_in(&self, workspace: usize) -> Vec<usize> {
+        self.sorted_items_in(workspace)
+            .into_iter()
+            .filter(|&index| {
+                let item = &self.items[workspace][index];
+                (self.show_snoozed || !item.is_snoozed()) && self.search.matches(item)
+            })
+            .collect()
+    }
+
+    pub fn sorted_items_in(&self, workspace: usize) -> Vec<usize> {
+        let items = &self.items[workspace];
+
+        let mut visible_items: Vec<usize> = (0..items.len()).collect();
+
+        match self.workspaces[workspace].sort {
+            SortMode::Manual => {}
+            SortMode::Deadline => visible_items.sort_by_key(|&index| {
+                (
+                    !items[index].has_expire_datetime,
+                    items[index].expire_datetime,
+                )
+            }),
+            SortMode::Priority => {
+                visible_items.sort_by_key(|&index| Reverse(items[index].priority))
+            }
+            SortMode::Status => visible_items.sort_by_key(|&index| {
+                if items[index].is_finished {
+                    2
+                } else if items[index].is_late {
+                    0
+                } else {
+                    1
+                }
+            }),
+            SortMode::Created => visible_items.sort_by_key(|&index| Reverse(items[index].created)),
+            SortMode::Alphabetical => {
+                visible_items.sort_by_cached_key(|&index| items[index].text.to_lowercase())
+            }
+        }
+
+        visible_items
+    }
+
+    pub fn visible_items(&self) -> Vec<usize> {
+        self.visible_items_in(self.current_workspace)
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        if self.items.is_empty() {
+            return None;
+        }
+
+        self.visible_items().get(self.current_item).copied()
+    }
+
+    // Makes the storage order of a workspace follow its current sort order, so
+    // it can be switched to manual sorting without items jumping around.
+    pub fn apply_sort(&mut self, workspace: usize) {
+        let order = self.sorted_items_in(workspace);
+        let mut items: Vec<Option<widget::ItemState>> =
+            self.items[workspace].drain(..).map(Some).collect();
+
+        self.items[workspace] = order
+            .into_iter()
+            .map(|index| items[index].take().unwrap())
+            .collect();
+        self.workspaces[workspace].sort = SortMode::Manual;
+        self.renumber_items(workspace);
+    }
+
+    pub fn renumber_items(&mut self, workspace: usize) {
+        for (slot, item) in self.items[workspace].iter_mut().enumerate() {
+            item.slot = slot;
+        }
+    }
+
+    // Items marked in the current workspace, plus the range between the visual
+    // anchor and the cursor.
+    pub fn selection(&self) -> Vec<usize> {
+        if self.items.is_empty() {
+            return Vec::new();
+        }
+
+        let visible_items = self.visible_items();
+
+        let range = match self
+            .visual_anchor
+            .and_then(|anchor| visible_items.iter().position(|&i| i == anchor))
+        {
+            Some(anchor) => {
+                let cursor = self.current_item.min(visible_items.len().saturating_sub(1));
+                anchor.min(cursor)..anchor.max(cursor) + 1
+            }
+            None => 0..0,
+        };
+
+        let mut selection: Vec<usize> = visible_items
+            .iter()
+            .enumerate()
+            .filter(|&(row, &index)| {
+                range.contains(&row) || self.items[self.current_workspace][index].is_marked
+            })
+            .map(|(_, &index)| index)
+            .collect();
+
+        selection.extend(
+            self.items[self.current_workspace]
+                .iter()
+                .enumerate()
+                .filter(|(index, item)| item.is_marked && !visible_items.contains(index))
+                .map(|(index, _)| index),
+        );
+
+        selection
+    }
+
+    // The selection if there is one, otherwise the item under the cursor.
+    pub fn targets(&self) -> Vec<usize> {
+        let selection = self.selection();
+
+        if selection.is_empty() {
+            self.selected_index().into_iter().collect()
+        } else {
+            selection
+        }
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.visual_anchor = None;
+        for items in self.items.iter_mut() {
+            for item in items.iter_mut() {
+                item.is_marked = false;
+            }
+        }
+    }
+
+    pub fn select_index(&mut self, index: usize) {
+        if let Some(row) = self.visible_items().iter().position(|&i| i == index) {
+            self.current_item = row;
+        }
+    }
+
+    // The count and chords typed so far, shown while a binding is incomplete.
+    pub fn pending_keys_text(&self) -> String {
+        let mut text = self
+            .count
+            .map(|count| count.to_string())
+            .unwrap_or_default();
+
+        for chord in &self.pending_keys {
+            text.push_str(&chord.name());
+        }
+
+        text
+    }
+
+    pub fn set_message(&mut self, text: String, is_error: bool) {
+        self.message = Some(StatusMessage {
+            text,
+            is_error,
+            time: Instant::now(),
+        });
+    }
+
+    pub fn show_toast(&mut self, text: String) {
+        self.toasts.push(StatusMessage {
+            text,
+            is_error: false,
+            time: Instant::now(),
+        });
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+    pub text: String,
+    pub is_error: bool,
+    pub time: Instant,
 }
 
+// First row shown by a list and how many rows fitted when it was last drawn.
 #[derive(Debug, Default, Clone, Copy)]
-pub struct SummaryScrollState {
+pub struct ScrollState {
     pub offset: usize,
-    pub queued_scroll: Option<ScrollDirection>,
+    pub height: usize,
 }
 
+// Where an item was drawn in the last frame, for mouse clicks. `mark_x` is
+// the column of its `[ ]` status mark on the top border.
 #[derive(Debug, Clone, Copy)]
-pub enum ScrollDirection {
-    Up,
-    Down,
+pub struct ItemArea {
+    pub area: Rect,
+    pub workspace: usize,
+    pub row: usize,
+    pub mark_x: u16,
 }
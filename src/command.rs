@@ -0,0 +1,184 @@
+use anyhow::{format_err, Error};
+
+use crate::app::App;
+use crate::export::ExportFormat;
+use crate::theme::PRESETS;
+use crate::widget::SortMode;
+use crate::CONFIG;
+
+// Name, arguments and description of every `:` command.
+pub const COMMANDS: &[(&str, &str, &str)] = &[
+    ("add", "[text]", "Add an item, or open the add item window"),
+    ("move", "<workspace>", "Move selected items to a workspace"),
+    ("sort", "<order>", "Sort the current workspace"),
+    ("filter", "[text | #tag]", "Show only matching items"),
+    ("theme", "<name>", "Switch colour theme"),
+    ("export", "<md | csv> <file>", "Export all workspaces"),
+    (
+        "archive",
+        "",
+        "Archive finished items of the current workspace",
+    ),
+    ("w", "", "Save"),
+    ("q", "", "Quit unless there are unsaved changes"),
+    ("q!", "", "Quit without saving"),
+    ("wq", "", "Save and quit"),
+    ("x", "", "Save and quit"),
+];
+
+pub enum Command {
+    Add(String),
+    Move(String),
+    Sort(SortMode),
+    Filter(String),
+    Theme(String),
+    Export(ExportFormat, String),
+    Archive,
+    Write,
+    Quit,
+    ForceQuit,
+    WriteQuit,
+}
+
+// Splits a command line into the command name and the rest, trimmed.
+fn split(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+
+    match text.find(char::is_whitespace) {
+        Some(end) => (&text[..end], text[end..].trim()),
+        None => (text, ""),
+    }
+}
+
+fn parse_sort(name: &str) -> Option<SortMode> {
+    match name {
+        "due" => Some(SortMode::Deadline),
+        "alpha" => Some(SortMode::Alphabetical),
+        name => SortMode::from_name(name),
+    }
+}
+
+pub fn parse(text: &str) -> Result<Command, Error> {
+    let (name, args) = split(text);
+
+    let needs_args = |usage: &str| {
+        if args.is_empty() {
+            Err(format_err!("Usage: :{} {}", name, usage))
+        } else {
+            Ok(args.to_string())
+        }
+    };
+
+    let command = match name {
+        "add" => Command::Add(args.to_string()),
+        "move" => Command::Move(needs_args("<workspace>")?),
+        "sort" => {
+            let order = needs_args("<order>")?;
+            Command::Sort(
+                parse_sort(&order).ok_or_else(|| format_err!("Unknown sort order '{}'", order))?,
+            )
+        }
+        "filter" => Command::Filter(args.to_string()),
+        "theme" => Command::Theme(needs_args("<name>")?),
+        "export" => {
+            let (format, path) = split(args);
+
+            match (ExportFormat::from_name(format), path) {
+                (Some(format), path) if !path.is_empty() => {
+                    Command::Export(format, path.to_string())
+                }
+                (None, _) if !format.is_empty() => {
+                    return Err(format_err!("Unknown export format '{}'", format))
+                }
+                _ => return Err(format_err!("Usage: :export <md | csv> <file>")),
+            }
+        }
+        "w" | "q" | "q!" | "wq" | "x" | "archive" if !args.is_empty() => {
+            return Err(format_err!(":{} takes no arguments", name))
+        }
+        "archive" => Command::Archive,
+        "w" => Command::Write,
+        "q" => Command::Quit,
+        "q!" => Command::ForceQuit,
+        "wq" | "x" => Command::WriteQuit,
+        "" => return Err(format_err!("Empty command")),
+        name => return Err(format_err!("Unknown command '{}'", name)),
+    };
+
+    Ok(command)
+}
+
+fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+    text.to_lowercase().starts_with(&prefix.to_lowercase())
+}
+
+// Candidates for the end of `text`, with the byte offset where the completed
+// part starts. Arguments that may contain spaces are completed as a whole.
+pub fn complete(app: &App, text: &str) -> (usize, Vec<String>) {
+    let start = text.len() - text.trim_start().len();
+    let (name, _) = split(text);
+
+    if start + name.len() == text.len() {
+        let names = COMMANDS
+            .iter()
+            .map(|&(name, _, _)| name.to_string())
+            .filter(|command| command.starts_with(name))
+            .collect();
+
+        return (start, names);
+    }
+
+    let args_start = text.len() - text[start + name.len()..].trim_start().len();
+    let args = &text[args_start..];
+
+    let mut candidates: Vec<String> = match name {
+        "move" => app
+            .workspaces
+            .iter()
+            .map(|workspace| workspace.title.clone())
+            .collect(),
+        "sort" => SortMode::ALL
+            .iter()
+            .map(|sort| sort.name().to_string())
+            .collect(),
+        "theme" => PRESETS
+            .iter()
+            .map(|name| name.to_string())
+            .chain(CONFIG.themes.keys().cloned())
+            .collect(),
+        "filter" => {
+            let mut tags: Vec<String> = app
+                .items
+                .iter()
+                .flatten()
+                .flat_map(|item| item.tags.iter().map(|tag| format!("#{}", tag)))
+                .collect();
+            tags.sort();
+            tags.dedup();
+            tags
+        }
+        "export" if !args.contains(char::is_whitespace) => ExportFormat::NAMES
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    candidates.retain(|candidate| starts_with_ignore_case(candidate, args));
+
+    (args_start, candidates)
+}
+
+// Usage line of the command being typed, once its name is complete.
+pub fn usage(text: &str) -> Option<String> {
+    let (name, _) = split(text);
+
+    if !text.trim_start()[name.len()..].starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    COMMANDS
+        .iter()
+        .find(|&&(command, _, _)| command == name)
+        .map(|&(command, args, description)| format!(":{} {} - {}", command, args, description))
+}
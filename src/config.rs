@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::{fs, process};
+
+use anyhow::{format_err, Error};
+use serde::Deserialize;
+
+use crate::keymap::KeymapMode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Autosave {
+    Off,
+    Change,
+    Debounce,
+}
+
+// How the time left until a deadline is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Countdown {
+    Verbose,
+    Compact,
+    Rounded,
+    Absolute,
+    Both,
+}
+
+impl Countdown {
+    pub const ALL: [Countdown; 5] = [
+        Countdown::Verbose,
+        Countdown::Compact,
+        Countdown::Rounded,
+        Countdown::Absolute,
+        Countdown::Both,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Countdown::Verbose => "verbose",
+            Countdown::Compact => "compact",
+            Countdown::Rounded => "rounded",
+            Countdown::Absolute => "absolute",
+            Countdown::Both => "both",
+        }
+    }
+
+    pub fn next(self) -> Countdown {
+        let index = Countdown::ALL
+            .iter()
+            .position(|&countdown| countdown == self)
+            .unwrap();
+        Countdown::ALL[(index + 1) % Countdown::ALL.len()]
+    }
+}
+
+// Key chords mapped to action names, on top of the default bindings.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct KeymapConfig {
+    pub item: BTreeMap<String, String>,
+    pub workspace: BTreeMap<String, String>,
+    pub item_prompt: BTreeMap<String, String>,
+    pub prompt: BTreeMap<String, String>,
+    pub search: BTreeMap<String, String>,
+    pub move_item: BTreeMap<String, String>,
+    pub agenda: BTreeMap<String, String>,
+    pub calendar: BTreeMap<String, String>,
+    pub calendar_day: BTreeMap<String, String>,
+    pub board: BTreeMap<String, String>,
+    pub dashboard: BTreeMap<String, String>,
+    pub archive: BTreeMap<String, String>,
+    pub trash: BTreeMap<String, String>,
+    pub help: BTreeMap<String, String>,
+    pub command_line: BTreeMap<String, String>,
+    pub palette: BTreeMap<String, String>,
+    pub confirm: BTreeMap<String, String>,
+}
+
+impl KeymapConfig {
+    pub fn overrides(&self, mode: KeymapMode) -> &BTreeMap<String, String> {
+        match mode {
+            KeymapMode::Item => &self.item,
+            KeymapMode::Workspace => &self.workspace,
+            KeymapMode::ItemPrompt => &self.item_prompt,
+            KeymapMode::Prompt => &self.prompt,
+            KeymapMode::Search => &self.search,
+            KeymapMode::MoveItem => &self.move_item,
+            KeymapMode::Agenda => &self.agenda,
+            KeymapMode::Calendar => &self.calendar,
+            KeymapMode::CalendarDay => &self.calendar_day,
+            KeymapMode::Board => &self.board,
+            KeymapMode::Dashboard => &self.dashboard,
+            KeymapMode::Archive => &self.archive,
+            KeymapMode::Trash => &self.trash,
+            KeymapMode::Help => &self.help,
+            KeymapMode::CommandLine => &self.command_line,
+            KeymapMode::Palette => &self.palette,
+            KeymapMode::Confirm => &self.confirm,
+        }
+    }
+}
+
+// Colours overriding those of the `base` preset, each a name, a 256-colour
+// index or `#rrggbb`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub base: Option<String>,
+    pub background: Option<String>,
+    pub unfinished: Option<String>,
+    pub finished: Option<String>,
+    pub loss: Option<String>,
+    pub text_normal: Option<String>,
+    pub text_primary: Option<String>,
+    pub text_secondary: Option<String>,
+    pub text_dark: Option<String>,
+    pub border_primary: Option<String>,
+    pub border_secondary: Option<String>,
+    pub border_axis: Option<String>,
+    pub focused: Option<String>,
+    pub unfocused: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub confirm: bool,
+    pub autosave: Autosave,
+    pub autosave_delay: u64,
+    pub countdown: Countdown,
+    pub reminders: Vec<String>,
+    pub notify_command: Option<Vec<String>>,
+    pub archive_after_days: Option<u64>,
+    pub trash_retention_days: u64,
+    pub mouse: bool,
+    pub sidebar: bool,
+    pub wip_limits: BTreeMap<String, usize>,
+    pub keymap: KeymapConfig,
+    pub theme: Option<String>,
+    pub themes: BTreeMap<String, ThemeConfig>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            confirm: true,
+            autosave: Autosave::Off,
+            autosave_delay: 2,
+            countdown: Countdown::Verbose,
+            reminders: vec!["1d".to_string(), "1h".to_string(), "0".to_string()],
+            notify_command: None,
+            archive_after_days: None,
+            trash_retention_days: 30,
+            mouse: true,
+            sidebar: true,
+            wip_limits: BTreeMap::new(),
+            keymap: KeymapConfig::default(),
+            theme: None,
+            themes: BTreeMap::new(),
+        }
+    }
+}
+
+fn get_config() -> Result<Config, Error> {
+    let config_path = dirs_next::config_dir()
+        .ok_or_else(|| format_err!("could not get config directory"))?
+        .join("tdr")
+        .join("config.toml");
+
+    let config = fs::read_to_string(&config_path)?;
+
+    match toml::from_str::<Config>(&config) {
+        Ok(config) => Ok(config),
+        Err(err) => {
+            eprintln!("tdr: {}: {}", config_path.display(), err);
+            process::exit(1);
+        }
+    }
+}
+
+pub fn resolve_config() -> Config {
+    get_config().unwrap_or_default()
+}
@@ -1,17 +1,26 @@
+use std::time::Duration;
+
 use tui::backend::Backend;
 use tui::layout::{Constraint, Direction, Layout, Rect};
-use tui::style::Modifier;
+use tui::style::{Modifier, Style};
 use tui::text::{Span, Spans, Text};
-use tui::widgets::{Block, Borders, Clear, Paragraph};
+use tui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
 use tui::{Frame, Terminal};
+use unicode_width::UnicodeWidthStr;
 
-use crate::app::{App, HelpMode, Mode, ScrollDirection};
-use crate::theme::style;
+use crate::app::{App, ItemArea, Mode, ScrollState};
+use crate::theme::{style, theme};
 use crate::widget::{
-    AddItemWidget, AddWorkspaceWidget, EditItemWidget, EditWorkspaceWidget, ItemWidget,
-    WorkspaceWidget, HELP_HEIGHT, HELP_WIDTH,
+    AddItemWidget, AddWorkspaceWidget, AgendaWidget, ArchiveWidget, BoardWidget, CalendarWidget,
+    CommandLineWidget, ConfirmWidget, DashboardWidget, DetailWidget, EditItemWidget,
+    EditWorkspaceWidget, HelpWidget, ItemWidget, PaletteWidget, PromptWidget, SearchWidget,
+    SortMode, TrashWidget, WorkspaceWidget, HELP_WIDTH,
 };
-use crate::THEME;
+use crate::CONFIG;
+
+const MESSAGE_DURATION: Duration = Duration::from_secs(3);
+const TOAST_DURATION: Duration = Duration::from_secs(10);
+const TOAST_WIDTH: u16 = 48;
 
 #[allow(dead_code)]
 pub enum PaddingDirection {
@@ -54,6 +63,77 @@ pub fn add_padding(mut rect: Rect, n: u16, direction: PaddingDirection) -> Rect
     }
 }
 
+// Splits `area` into `n` parts whose sizes differ by at most one, without the
+// gaps ratio constraints leave.
+pub fn split_evenly(area: Rect, n: u16, direction: Direction) -> Vec<Rect> {
+    let total = match direction {
+        Direction::Horizontal => area.width,
+        Direction::Vertical => area.height,
+    };
+
+    (0..n)
+        .map(|part| {
+            let start = total * part / n;
+            let size = total * (part + 1) / n - start;
+
+            match direction {
+                Direction::Horizontal => Rect {
+                    x: area.x + start,
+                    width: size,
+                    ..area
+                },
+                Direction::Vertical => Rect {
+                    y: area.y + start,
+                    height: size,
+                    ..area
+                },
+            }
+        })
+        .collect()
+}
+
+fn message_span(app: &mut App) -> Option<Span<'static>> {
+    if let Some(message) = &app.message {
+        if message.time.elapsed() > MESSAGE_DURATION {
+            app.message = None;
+        }
+    }
+
+    app.message.as_ref().map(|message| {
+        Span::styled(
+            message.text.clone(),
+            if message.is_error {
+                style().add_modifier(Modifier::BOLD).fg(theme().loss)
+            } else {
+                style().fg(theme().text_secondary)
+            },
+        )
+    })
+}
+
+// Moves the offset of `scroll` just far enough for row `selected` to be shown,
+// however far the cursor jumped since the last frame, and records how many
+// rows fit.
+fn update_scroll(
+    scroll: &mut ScrollState,
+    selected: usize,
+    num_to_render: usize,
+    len: usize,
+) -> usize {
+    let mut offset = scroll.offset;
+
+    if selected < offset {
+        offset = selected;
+    } else if selected >= offset + num_to_render {
+        offset = selected + 1 - num_to_render;
+    }
+
+    scroll.offset = offset.min(len - num_to_render);
+    scroll.height = num_to_render;
+
+    scroll.offset
+}
+
 fn draw_add_workspace<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
     frame.render_stateful_widget(AddWorkspaceWidget {}, area, &mut app.add_workspace);
 }
@@ -62,11 +142,69 @@ fn draw_edit_workspace<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Re
     frame.render_stateful_widget(EditWorkspaceWidget {}, area, &mut app.edit_workspace);
 }
 
+// Frame width from which the workspaces stay shown next to the items.
+const SIDEBAR_MIN_WIDTH: u16 = 100;
+const SIDEBAR_WIDTH: u16 = 32;
+
+// Whether the workspace list, rather than the items, has the keyboard. Modes
+// drawn over another one keep the focus of the mode below.
+fn is_workspace_focused(app: &App) -> bool {
+    let mode = match app.mode {
+        Mode::CommandLine | Mode::Palette | Mode::Confirm => app.previous_mode,
+        mode => mode,
+    };
+
+    matches!(
+        mode,
+        Mode::DisplayWorkspace | Mode::AddWorkspace | Mode::EditWorkspace | Mode::MoveItem
+    )
+}
+
+// Border of a pane, marking the focused one when both are shown.
+fn pane_border_style(app: &App, is_workspace: bool) -> Style {
+    if !app.has_sidebar {
+        style().fg(theme().border_primary)
+    } else if is_workspace == is_workspace_focused(app) {
+        style().fg(theme().focused)
+    } else {
+        style().fg(theme().unfocused)
+    }
+}
+
+// Draws the workspaces next to the items on wide frames, otherwise only the
+// pane that has the focus.
+fn draw_panes<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    app.has_sidebar =
+        CONFIG.sidebar && area.width >= SIDEBAR_MIN_WIDTH && !app.workspaces.is_empty();
+
+    if app.has_sidebar {
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(SIDEBAR_WIDTH), Constraint::Min(0)].as_ref())
+            .split(area);
+
+        draw_workspace(frame, app, layout[0]);
+        draw_item(frame, app, layout[1]);
+    } else if is_workspace_focused(app) {
+        draw_workspace(frame, app, area);
+    } else {
+        draw_item(frame, app, area);
+    }
+}
+
 fn draw_workspace<B: Backend>(frame: &mut Frame<B>, app: &mut App, mut area: Rect) {
+    let title = if app.mode == Mode::MoveItem {
+        " Move Item to Workspace "
+    } else if app.has_sidebar {
+        " Workspaces "
+    } else {
+        " Workspace "
+    };
+
     let border = Block::default()
         .borders(Borders::ALL)
-        .border_style(style().fg(THEME.border_primary))
-        .title(Span::styled(" Workspace ", style().fg(THEME.text_normal)));
+        .border_style(pane_border_style(app, true))
+        .title(Span::styled(title, style().fg(theme().text_normal)));
     frame.render_widget(border, area);
     area = add_padding(area, 1, PaddingDirection::All);
 
@@ -75,42 +213,36 @@ fn draw_workspace<B: Backend>(frame: &mut Frame<B>, app: &mut App, mut area: Rec
     let num_to_render =
         (((height - 3) / workspace_widget_height) as usize).min(app.workspaces.len());
 
-    let mut scroll_offset = if let Some(direction) = app.summary_scroll_state.queued_scroll.take() {
-        let new_offset = match direction {
-            ScrollDirection::Up => {
-                if app.summary_scroll_state.offset == 0 {
-                    0
-                } else {
-                    (app.summary_scroll_state.offset - 1).min(app.workspaces.len())
-                }
-            }
-            ScrollDirection::Down => {
-                (app.summary_scroll_state.offset + 1).min(app.workspaces.len() - num_to_render)
-            }
-        };
-
-        app.summary_scroll_state.offset = new_offset;
-
-        new_offset
-    } else {
-        app.summary_scroll_state.offset
-    };
-
-    if num_to_render + scroll_offset > app.workspaces.len() {
-        scroll_offset -= (num_to_render + scroll_offset) - app.workspaces.len();
-        app.summary_scroll_state.offset = scroll_offset;
-    }
+    let scroll_offset = update_scroll(
+        &mut app.workspace_scroll,
+        app.current_workspace,
+        num_to_render,
+        app.workspaces.len(),
+    );
 
     let layout = Layout::default()
         .constraints(
             [
                 Constraint::Length(1),
                 Constraint::Length((num_to_render * workspace_widget_height as usize) as u16),
+                Constraint::Min(0),
             ]
             .as_ref(),
         )
         .split(area);
 
+    if let Some(message) = message_span(app) {
+        let message_area = add_padding(
+            layout[2],
+            layout[2].height.saturating_sub(1),
+            PaddingDirection::Top,
+        );
+        frame.render_widget(
+            Paragraph::new(Spans::from(message)),
+            add_padding(message_area, 1, PaddingDirection::Left),
+        );
+    }
+
     let constraints = app.workspaces[scroll_offset..num_to_render + scroll_offset]
         .iter()
         .map(|_| Constraint::Length(workspace_widget_height))
@@ -122,7 +254,18 @@ fn draw_workspace<B: Backend>(frame: &mut Frame<B>, app: &mut App, mut area: Rec
         .iter_mut()
         .enumerate()
     {
-        frame.render_stateful_widget(WorkspaceWidget {}, workspace_layout[idx], workspace);
+        let items = &app.items[scroll_offset + idx];
+        let workspace_widget = WorkspaceWidget {
+            num_of_pending: items
+                .iter()
+                .filter(|i| !i.is_finished && !i.is_late)
+                .count(),
+            num_of_late: items.iter().filter(|i| !i.is_finished && i.is_late).count(),
+        };
+
+        frame.render_stateful_widget(workspace_widget, workspace_layout[idx], workspace);
+        app.workspace_areas
+            .push((workspace_layout[idx], scroll_offset + idx));
     }
 }
 
@@ -134,93 +277,301 @@ fn draw_edit_item<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
     frame.render_stateful_widget(EditItemWidget {}, area, &mut app.edit_item);
 }
 
-fn draw_item<B: Backend>(frame: &mut Frame<B>, app: &mut App, mut area: Rect) {
-    let border = Block::default()
-        .borders(Borders::ALL)
-        .border_style(style().fg(THEME.border_primary))
-        .title(Span::styled(" Item ", style().fg(THEME.text_normal)));
-    frame.render_widget(border, area);
-    area = add_padding(area, 1, PaddingDirection::All);
+fn draw_prompt<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    frame.render_stateful_widget(PromptWidget {}, area, &mut app.prompt);
+}
+
+fn draw_command_line<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    frame.render_stateful_widget(CommandLineWidget {}, area, &mut app.command_line);
+}
+
+fn draw_palette<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    draw_panes(frame, app, area);
+
+    let layout = app.palette.get_rect(area);
+    frame.render_stateful_widget(PaletteWidget {}, layout, &mut app.palette);
+}
+
+fn draw_agenda<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    let agenda_widget = AgendaWidget {
+        items: &app.items,
+        workspaces: &app.workspaces,
+        show_snoozed: app.show_snoozed,
+    };
+    frame.render_stateful_widget(agenda_widget, area, &mut app.agenda);
+}
+
+fn draw_calendar<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    let calendar_widget = CalendarWidget {
+        items: &app.items,
+        workspaces: &app.workspaces,
+        show_snoozed: app.show_snoozed,
+    };
+    frame.render_stateful_widget(calendar_widget, area, &mut app.calendar);
+}
+
+fn draw_board<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    let visible_items = app.visible_items();
+    let board_widget = BoardWidget {
+        title: &app.workspaces[app.current_workspace].title,
+        items: &app.items[app.current_workspace],
+        visible: &visible_items,
+    };
+    frame.render_stateful_widget(board_widget, area, &mut app.board);
+
+    if let Some(message) = message_span(app) {
+        let message_area = Rect {
+            x: area.x + 2,
+            y: area.y + area.height.saturating_sub(1),
+            width: area.width.saturating_sub(4),
+            height: 1,
+        };
+        frame.render_widget(Paragraph::new(Spans::from(message)), message_area);
+    }
+}
+
+fn draw_dashboard<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    let dashboard_widget = DashboardWidget {
+        items: &app.items,
+        workspaces: &app.workspaces,
+    };
+    frame.render_widget(dashboard_widget, area);
+}
 
-    let num_of_item = app.items[app.current_workspace].len();
+fn draw_archive<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    let archive_widget = ArchiveWidget {
+        archived: &app.archived,
+    };
+    frame.render_stateful_widget(archive_widget, area, &mut app.archive);
+}
+
+fn draw_trash<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    let trash_widget = TrashWidget {
+        items: &app.trashed_items,
+        workspaces: &app.trashed_workspaces,
+    };
+    frame.render_stateful_widget(trash_widget, area, &mut app.trash);
+}
+
+fn draw_search<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    frame.render_stateful_widget(SearchWidget {}, area, &mut app.search);
+}
+
+fn draw_item_list<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    let visible_items = app.visible_items();
+    let selection = app.selection();
+    let num_of_item = visible_items.len();
 
     let item_widget_height = 3;
     let height = area.height;
-    let num_to_render = (((height - 3) / item_widget_height) as usize).min(num_of_item);
-
-    let scroll_offset = if let Some(direction) = app.summary_scroll_state.queued_scroll.take() {
-        let new_offset = match direction {
-            ScrollDirection::Up => {
-                if app.summary_scroll_state.offset > 0 {
-                    let scrolloff = (app.summary_scroll_state.offset - 1).min(num_of_item);
-                    if app.current_item - 1 <= app.items[app.current_workspace][scrolloff].slot {
-                        scrolloff
-                    } else {
-                        app.summary_scroll_state.offset
-                    }
-                } else {
-                    0
-                }
-            }
-            ScrollDirection::Down => {
-                let scrolloff =
-                    (app.summary_scroll_state.offset + 1).min(num_of_item - num_to_render);
-                if !app.items[app.current_workspace].is_empty()
-                    && app.current_item + 1
-                        >= app.items[app.current_workspace][scrolloff + num_to_render - 1].slot
-                {
-                    scrolloff
-                } else {
-                    app.summary_scroll_state.offset
-                }
+    let num_to_render = ((height / item_widget_height) as usize).min(num_of_item);
+
+    let scroll_offset = update_scroll(
+        &mut app.item_scroll,
+        app.current_item,
+        num_to_render,
+        num_of_item,
+    );
+
+    let starting_index = scroll_offset;
+
+    let constraints = visible_items[starting_index..starting_index + num_to_render]
+        .iter()
+        .map(|_i| Constraint::Length(item_widget_height))
+        .collect::<Vec<_>>();
+
+    let item_layout = Layout::default().constraints(constraints).split(area);
+
+    for (idx, &index) in visible_items[starting_index..starting_index + num_to_render]
+        .iter()
+        .enumerate()
+    {
+        let is_marked = selection.contains(&index);
+        let item = &mut app.items[app.current_workspace][index];
+
+        app.item_areas.push(ItemArea {
+            area: item_layout[idx],
+            workspace: app.current_workspace,
+            row: starting_index + idx,
+            mark_x: item_layout[idx].x + item.mark_offset(is_marked),
+        });
+
+        frame.render_stateful_widget(
+            ItemWidget {
+                highlight: app.search.input.text(),
+                is_marked,
+                countdown: app.countdown,
+            },
+            item_layout[idx],
+            item,
+        );
+    }
+}
+
+// Renders the hits of a search across all workspaces, grouped under the title
+// of the workspace they belong to.
+fn draw_search_results<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    let item_widget_height = 3;
+    let header_height = 1;
+
+    let mut rows: Vec<(usize, Option<(usize, usize)>)> = Vec::new();
+    let mut selected_row = 0;
+
+    for workspace in 0..app.workspaces.len() {
+        let visible_items = app.visible_items_in(workspace);
+
+        if visible_items.is_empty() {
+            continue;
+        }
+
+        rows.push((workspace, None));
+        for (row, index) in visible_items.into_iter().enumerate() {
+            if workspace == app.current_workspace && row == app.current_item {
+                selected_row = rows.len();
             }
+            rows.push((workspace, Some((row, index))));
+        }
+    }
+
+    let row_height = |row: &(usize, Option<(usize, usize)>)| {
+        if row.1.is_some() {
+            item_widget_height
+        } else {
+            header_height
+        }
+    };
+
+    let mut scroll_offset = app.item_scroll.offset.min(rows.len());
+
+    if selected_row > 0 && selected_row - 1 < scroll_offset {
+        scroll_offset = selected_row - 1;
+    }
+    while scroll_offset < selected_row
+        && rows[scroll_offset..=selected_row]
+            .iter()
+            .map(row_height)
+            .sum::<u16>()
+            > area.height
+    {
+        scroll_offset += 1;
+    }
+    app.item_scroll.offset = scroll_offset;
+
+    let mut y = area.y;
+    for &(workspace, entry) in rows[scroll_offset..].iter() {
+        let height = row_height(&(workspace, entry));
+
+        if y + height > area.y + area.height {
+            break;
+        }
+
+        let row_area = Rect {
+            x: area.x,
+            y,
+            width: area.width,
+            height,
         };
 
-        app.summary_scroll_state.offset = new_offset;
+        match entry {
+            Some((row, index)) => {
+                let item = &mut app.items[workspace][index];
+
+                app.item_areas.push(ItemArea {
+                    area: row_area,
+                    workspace,
+                    row,
+                    mark_x: row_area.x + item.mark_offset(false),
+                });
+
+                frame.render_stateful_widget(
+                    ItemWidget {
+                        highlight: app.search.input.text(),
+                        is_marked: false,
+                        countdown: app.countdown,
+                    },
+                    row_area,
+                    item,
+                );
+            }
+            None => frame.render_widget(
+                Paragraph::new(Spans::from(Span::styled(
+                    format!(" {} ", app.workspaces[workspace].title),
+                    style()
+                        .add_modifier(Modifier::BOLD)
+                        .fg(theme().text_primary),
+                ))),
+                row_area,
+            ),
+        }
+
+        y += height;
+    }
+}
 
-        new_offset
+// Terminal size from which the detail pane goes to the right of the items,
+// or below them when only the height is enough.
+const DETAIL_SIDE_WIDTH: u16 = 100;
+const DETAIL_BOTTOM_HEIGHT: u16 = 24;
+
+// Splits off the detail pane from `area` if it is shown and fits, returning
+// the area left for the items.
+fn draw_details<B: Backend>(frame: &mut Frame<B>, app: &App, area: Rect) -> Rect {
+    let direction = if !app.show_details {
+        return area;
+    } else if area.width >= DETAIL_SIDE_WIDTH {
+        Direction::Horizontal
+    } else if area.height >= DETAIL_BOTTOM_HEIGHT {
+        Direction::Vertical
     } else {
-        app.summary_scroll_state.offset
+        return area;
     };
 
+    let layout = Layout::default()
+        .direction(direction)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+        .split(area);
+
+    let item = app
+        .selected_index()
+        .map(|index| &app.items[app.current_workspace][index]);
+    frame.render_widget(DetailWidget { item }, layout[1]);
+
+    layout[0]
+}
+
+fn draw_item<B: Backend>(frame: &mut Frame<B>, app: &mut App, mut area: Rect) {
+    area = draw_details(frame, app, area);
+
+    let border = Block::default()
+        .borders(Borders::ALL)
+        .border_style(pane_border_style(app, false))
+        .title(Span::styled(" Item ", style().fg(theme().text_normal)));
+    frame.render_widget(border, area);
+    area = add_padding(area, 1, PaddingDirection::All);
+
     let mut layout = Layout::default()
         .constraints(
             [
                 Constraint::Length(1),
-                Constraint::Length((num_to_render * item_widget_height as usize) as u16),
                 Constraint::Min(0),
+                Constraint::Length(2),
             ]
             .as_ref(),
         )
         .split(area);
 
-    let starting_index = scroll_offset;
-
-    let constraints = app.items[app.current_workspace]
-        [starting_index..starting_index + num_to_render]
-        .iter()
-        .map(|_i| Constraint::Length(item_widget_height))
-        .collect::<Vec<_>>();
-
-    let item_layout = Layout::default().constraints(constraints).split(layout[1]);
-
-    for (idx, item) in app.items[app.current_workspace]
-        [starting_index..starting_index + num_to_render]
-        .iter_mut()
-        .enumerate()
-    {
-        frame.render_stateful_widget(ItemWidget {}, item_layout[idx], item);
+    if app.search.is_active() && app.search.all_workspaces {
+        draw_search_results(frame, app, layout[1]);
+    } else {
+        draw_item_list(frame, app, layout[1]);
     }
 
-    layout[2] = add_padding(layout[2], 1, PaddingDirection::Left);
-    frame.render_widget(Clear, layout[2]);
-    frame.render_widget(Block::default().style(style()), layout[2]);
+    let num_of_item = app.visible_items().len();
 
-    let offset = layout[2].height - 2;
-    layout[2] = add_padding(layout[2], offset, PaddingDirection::Top);
+    layout[2] = add_padding(layout[2], 1, PaddingDirection::Left);
 
     frame.render_widget(
-        Block::default().border_style(style().fg(THEME.border_secondary)),
+        Block::default().border_style(style().fg(theme().border_secondary)),
         layout[2],
     );
 
@@ -232,82 +583,153 @@ fn draw_item<B: Backend>(frame: &mut Frame<B>, app: &mut App, mut area: Rect) {
         .constraints(
             [
                 Constraint::Min(0),
-                Constraint::Length(
-                    app.workspaces[app.current_workspace]
-                        .num_of_item
-                        .to_string()
-                        .len() as u16
-                        * 2
-                        + 1,
-                ),
+                Constraint::Length(num_of_item.to_string().len() as u16 * 2 + 1),
             ]
             .as_ref(),
         )
         .split(layout[2]);
 
-    frame.render_widget(
-        Paragraph::new(Spans::from(Span::styled(
+    let mut status = vec![Span::styled(
+        format!(
+            "{}: {}[{} -, {} ✓, {} x]{}",
+            app.workspaces[app.current_workspace].title.clone(),
+            if app.is_modified { "[+]" } else { "" },
+            app.items[app.current_workspace]
+                .iter()
+                .filter(|i| i.is_finished == false && i.is_late == false)
+                .count(),
+            app.items[app.current_workspace]
+                .iter()
+                .filter(|i| i.is_finished == true)
+                .count(),
+            app.items[app.current_workspace]
+                .iter()
+                .filter(|i| i.is_finished == false && i.is_late == true)
+                .count(),
+            match app.workspaces[app.current_workspace].sort {
+                SortMode::Manual => String::new(),
+                sort => format!(" sorted by {}", sort.name()),
+            },
+        ),
+        style().add_modifier(Modifier::BOLD).fg(theme().unfocused),
+    )];
+
+    let num_of_selected = app.selection().len();
+    if num_of_selected > 0 || app.visual_anchor.is_some() {
+        status.push(Span::styled(
             format!(
-                "{}: {}[{} -, {} ✓, {} x]",
-                app.workspaces[app.current_workspace].title.clone(),
-                if app.is_modified { "[+]" } else { "" },
-                app.items[app.current_workspace]
-                    .iter()
-                    .filter(|i| i.is_finished == false && i.is_late == false)
-                    .count(),
-                app.items[app.current_workspace]
-                    .iter()
-                    .filter(|i| i.is_finished == true)
-                    .count(),
-                app.items[app.current_workspace]
-                    .iter()
-                    .filter(|i| i.is_finished == false && i.is_late == true)
-                    .count(),
+                " {}[{} selected]",
+                if app.visual_anchor.is_some() {
+                    "-- VISUAL -- "
+                } else {
+                    ""
+                },
+                num_of_selected
             ),
-            style().add_modifier(Modifier::BOLD).fg(THEME.unfocused),
-        ))),
-        bottom_layout[0],
-    );
+            style().add_modifier(Modifier::BOLD).fg(theme().focused),
+        ));
+    }
+
+    let pending_keys = app.pending_keys_text();
+    if !pending_keys.is_empty() {
+        status.push(Span::styled(
+            format!(" {}", pending_keys),
+            style().add_modifier(Modifier::BOLD).fg(theme().focused),
+        ));
+    }
+
+    if let Some(message) = message_span(app) {
+        status.push(Span::raw(" "));
+        status.push(message);
+    }
+
+    frame.render_widget(Paragraph::new(Spans::from(status)), bottom_layout[0]);
 
     frame.render_widget(
         Paragraph::new(Spans::from(Span::styled(
             format!(
                 "{}/{}",
-                if app.workspaces[app.current_workspace].num_of_item > 0 {
+                if num_of_item > 0 {
                     app.current_item + 1
                 } else {
                     0
                 },
-                app.workspaces[app.current_workspace].num_of_item,
+                num_of_item,
             ),
-            style().add_modifier(Modifier::BOLD).fg(THEME.unfocused),
+            style().add_modifier(Modifier::BOLD).fg(theme().unfocused),
         ))),
         bottom_layout[1],
     );
 }
 
 fn draw_help<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
-    let mut layout = area;
-
-    if layout.width < HELP_WIDTH as u16 || layout.height < HELP_HEIGHT as u16 {
+    if area.width < HELP_WIDTH as u16 {
         frame.render_widget(
             Paragraph::new(Text::styled(
                 "Increase screen size to display help",
                 style(),
             )),
-            layout,
+            area,
         );
     } else {
-        match app.help_mode {
-            HelpMode::ItemHelp => {
-                layout = app.help_item.get_rect(layout);
-                frame.render_widget(app.help_item, layout)
-            }
-            HelpMode::WorkspaceHelp => {
-                layout = app.help_workspace.get_rect(layout);
-                frame.render_widget(app.help_workspace, layout)
-            }
+        let layout = app.help.get_rect(area);
+        frame.render_stateful_widget(HelpWidget {}, layout, &mut app.help);
+    }
+}
+
+fn draw_confirm<B: Backend>(frame: &mut Frame<B>, app: &mut App, area: Rect) {
+    draw_panes(frame, app, area);
+
+    let layout = app.confirm.get_rect(area);
+    frame.render_stateful_widget(ConfirmWidget {}, layout, &mut app.confirm);
+}
+
+// Reminders stacked in the top right corner over whatever view is shown,
+// newest at the top, until they expire.
+fn draw_toasts<B: Backend>(frame: &mut Frame<B>, app: &mut App) {
+    app.toasts
+        .retain(|toast| toast.time.elapsed() <= TOAST_DURATION);
+
+    let size = frame.size();
+    let width = TOAST_WIDTH.min(size.width.saturating_sub(2));
+    let mut y = size.y + 1;
+
+    for toast in app.toasts.iter().rev() {
+        // Leaves a spare line for words wrapped early.
+        let lines = toast.text.width() as u16 / width.saturating_sub(2).max(1) + 1;
+        let height = 2 + lines + (lines > 1) as u16;
+
+        if y + height > size.bottom() {
+            break;
         }
+
+        let area = Rect {
+            x: size.right() - width - 1,
+            y,
+            width,
+            height,
+        };
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                toast.text.clone(),
+                style().fg(theme().text_normal),
+            ))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(style().fg(theme().focused))
+                    .title(Span::styled(
+                        " Reminder ",
+                        style().fg(theme().text_secondary),
+                    )),
+            )
+            .style(style())
+            .wrap(Wrap { trim: true }),
+            area,
+        );
+
+        y += height;
     }
 }
 
@@ -318,6 +740,16 @@ pub fn draw<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) {
         return;
     }
 
+    // Another program used the screen, so nothing on it can be trusted.
+    if app.needs_clear {
+        terminal.clear().unwrap();
+        app.needs_clear = false;
+    }
+
+    app.item_areas.clear();
+    app.workspace_areas.clear();
+    app.has_sidebar = false;
+
     terminal
         .draw(|mut frame| {
             frame.render_widget(Block::default().style(style()), frame.size());
@@ -328,23 +760,44 @@ pub fn draw<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) {
                         .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
                         .split(frame.size());
 
-                    if !app.items.is_empty() {
-                        draw_item(&mut frame, app, layout[0]);
-                    }
-
-                    draw_item(&mut frame, app, layout[0]);
+                    draw_panes(&mut frame, app, layout[0]);
                     draw_add_item(&mut frame, app, layout[1]);
                 }
+                Mode::Search => {
+                    let layout = Layout::default()
+                        .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
+                        .split(frame.size());
+
+                    draw_panes(&mut frame, app, layout[0]);
+                    draw_search(&mut frame, app, layout[1]);
+                }
+                Mode::Prompt => {
+                    let layout = Layout::default()
+                        .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
+                        .split(frame.size());
+
+                    draw_panes(&mut frame, app, layout[0]);
+                    draw_prompt(&mut frame, app, layout[1]);
+                }
+                Mode::CommandLine => {
+                    let layout = Layout::default()
+                        .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
+                        .split(frame.size());
+
+                    draw_panes(&mut frame, app, layout[0]);
+                    draw_command_line(&mut frame, app, layout[1]);
+                }
                 Mode::EditItem => {
                     let layout = Layout::default()
                         .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
                         .split(frame.size());
 
-                    if !app.items[app.current_workspace].is_empty() {
-                        draw_item(&mut frame, app, layout[0]);
+                    match app.previous_mode {
+                        Mode::Agenda => draw_agenda(&mut frame, app, layout[0]),
+                        Mode::Calendar => draw_calendar(&mut frame, app, layout[0]),
+                        Mode::Board => draw_board(&mut frame, app, layout[0]),
+                        _ => draw_panes(&mut frame, app, layout[0]),
                     }
-
-                    draw_item(&mut frame, app, layout[0]);
                     draw_edit_item(&mut frame, app, layout[1]);
                 }
                 Mode::AddWorkspace => {
@@ -352,11 +805,7 @@ pub fn draw<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) {
                         .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
                         .split(frame.size());
 
-                    if !app.workspaces.is_empty() {
-                        draw_workspace(&mut frame, app, layout[0]);
-                    }
-
-                    draw_workspace(&mut frame, app, layout[0]);
+                    draw_panes(&mut frame, app, layout[0]);
                     draw_add_workspace(&mut frame, app, layout[1]);
                 }
                 Mode::EditWorkspace => {
@@ -364,23 +813,31 @@ pub fn draw<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) {
                         .constraints([Constraint::Min(0), Constraint::Length(3)].as_ref())
                         .split(frame.size());
 
-                    if !app.workspaces.is_empty() {
-                        draw_workspace(&mut frame, app, layout[0]);
-                    }
-
-                    draw_workspace(&mut frame, app, layout[0]);
+                    draw_panes(&mut frame, app, layout[0]);
                     draw_edit_workspace(&mut frame, app, layout[1]);
                 }
                 _ => {
                     let layout = frame.size();
                     match app.mode {
                         Mode::DisplayHelp => draw_help(&mut frame, app, layout),
-                        Mode::DisplayWorkspace => draw_workspace(&mut frame, app, layout),
-                        Mode::DisplayItem => draw_item(&mut frame, app, layout),
+                        Mode::MoveItem => draw_workspace(&mut frame, app, layout),
+                        Mode::DisplayWorkspace | Mode::DisplayItem => {
+                            draw_panes(&mut frame, app, layout)
+                        }
+                        Mode::Confirm => draw_confirm(&mut frame, app, layout),
+                        Mode::Palette => draw_palette(&mut frame, app, layout),
+                        Mode::Agenda => draw_agenda(&mut frame, app, layout),
+                        Mode::Calendar => draw_calendar(&mut frame, app, layout),
+                        Mode::Board => draw_board(&mut frame, app, layout),
+                        Mode::Dashboard => draw_dashboard(&mut frame, app, layout),
+                        Mode::Archive => draw_archive(&mut frame, app, layout),
+                        Mode::Trash => draw_trash(&mut frame, app, layout),
                         _ => {}
                     }
                 }
             }
+
+            draw_toasts(&mut frame, app);
         })
         .unwrap();
 }
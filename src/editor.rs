@@ -0,0 +1,388 @@
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process::{self, Command};
+
+use anyhow::{format_err, Error};
+
+use crate::reminder;
+use crate::widget::ItemState;
+use crate::{cleanup_terminal, pause_ui_events, resume_ui_events, setup_terminal};
+
+const WORKSPACE_HELP: &str = "\
+# One item per line: <number> [ ] <text> | <deadline> | #<tag> ...
+# Use [x] for finished items. Lines without a number create new items and
+# deleted lines remove their item. Lines starting with '#' are ignored.
+# A backslash keeps the next character as text, as in \\| or a leading \\[.
+";
+
+// Escapes what `parse_workspace` would otherwise read as markup: backslashes,
+// field separators, and with `is_start` a leading checkbox or comment.
+fn escape(text: &str, is_start: bool) -> String {
+    let mut escaped = String::new();
+
+    for (position, c) in text.chars().enumerate() {
+        if c == '\\' || c == '|' || (is_start && position == 0 && (c == '[' || c == '#')) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            unescaped.extend(chars.next());
+        } else {
+            unescaped.push(c);
+        }
+    }
+
+    unescaped
+}
+
+// Splits a line at the ` | ` separators that are not escaped. The escapes are
+// kept for `unescape` or `parse_tags`.
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().unwrap();
+
+        if c == '\\' {
+            field.push(c);
+            field.extend(chars.next());
+        } else if c == ' ' && chars.as_str().starts_with("| ") {
+            chars.nth(1);
+            fields.push(String::new());
+        } else {
+            field.push(c);
+        }
+    }
+
+    fields
+}
+
+// Tags are separated by whitespace, so whitespace inside a tag is escaped
+// along with backslashes.
+fn format_tags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| {
+            let mut escaped = String::from("#");
+            for c in tag.chars() {
+                if c == '\\' || c.is_whitespace() {
+                    escaped.push('\\');
+                }
+                escaped.push(c);
+            }
+            escaped
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags = vec![String::new()];
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        let tag = tags.last_mut().unwrap();
+
+        if c == '\\' {
+            tag.extend(chars.next());
+        } else if c.is_whitespace() {
+            tags.push(String::new());
+        } else {
+            tag.push(c);
+        }
+    }
+
+    tags.into_iter()
+        .map(|tag| tag.trim_start_matches('#').to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+pub fn format_item(item: &ItemState) -> String {
+    format!(
+        "---\ntext: {}\ndeadline: {}\ntags: {}\nreminders: {}\n---\n{}",
+        item.text,
+        item.expire_datetime_string,
+        format_tags(&item.tags),
+        reminder::format_offsets(&item.reminders),
+        item.notes,
+    )
+}
+
+// Reads back the front matter and notes written by `format_item`.
+pub fn parse_item(text: &str, item: &mut ItemState) -> Result<(), Error> {
+    let mut lines = text.lines();
+
+    if lines.next().map(str::trim_end) != Some("---") {
+        return Err(format_err!("missing front matter"));
+    }
+
+    let mut item_text = None;
+    let mut deadline = None;
+    let mut tags = None;
+    let mut reminders = None;
+    let mut is_closed = false;
+
+    for line in &mut lines {
+        if line.trim_end() == "---" {
+            is_closed = true;
+            break;
+        }
+
+        let (key, value) = match line.find(':') {
+            Some(colon) => (line[..colon].trim(), line[colon + 1..].trim()),
+            None if line.trim().is_empty() => continue,
+            None => return Err(format_err!("invalid line `{}`", line)),
+        };
+
+        match key {
+            "text" => item_text = Some(value.to_string()),
+            "deadline" => deadline = Some(value.to_string()),
+            "tags" => tags = Some(parse_tags(value)),
+            "reminders" => reminders = Some(reminder::parse_offsets(value)?),
+            _ => return Err(format_err!("unknown field `{}`", key)),
+        }
+    }
+
+    if !is_closed {
+        return Err(format_err!("unterminated front matter"));
+    }
+
+    let notes = lines.collect::<Vec<_>>().join("\n");
+
+    if let Some(item_text) = item_text.filter(|text| !text.is_empty()) {
+        item.text = item_text;
+    }
+    if let Some(deadline) = deadline {
+        item.set_expire_datetime(deadline);
+    }
+    if let Some(tags) = tags {
+        item.tags = tags;
+    }
+    if let Some(reminders) = reminders {
+        item.reminders = reminders;
+    }
+    item.notes = notes.trim_end().to_string();
+
+    Ok(())
+}
+
+pub fn format_workspace<'a>(title: &str, items: impl Iterator<Item = &'a ItemState>) -> String {
+    let mut text = format!("# {}\n{}\n", title, WORKSPACE_HELP);
+
+    for (number, item) in items.enumerate() {
+        let mut line = format!(
+            "{} [{}] {}",
+            number + 1,
+            if item.is_finished { 'x' } else { ' ' },
+            escape(&item.text, true),
+        );
+
+        if item.has_expire_datetime || !item.tags.is_empty() {
+            line.push_str(&format!(
+                " | {}",
+                escape(&item.expire_datetime_string, false)
+            ));
+        }
+        if !item.tags.is_empty() {
+            line.push_str(&format!(" | {}", format_tags(&item.tags)));
+        }
+
+        text.push_str(&line);
+        text.push('\n');
+    }
+
+    text
+}
+
+// Rebuilds a workspace from the lines written by `format_workspace`. `items`
+// are the original items in the numbered order; the ones whose line was
+// removed are left in it.
+pub fn parse_workspace(text: &str, items: &mut [Option<ItemState>]) -> Vec<ItemState> {
+    let mut new_items = Vec::new();
+
+    for line in text.lines() {
+        let mut line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let digits = line
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(line.len());
+        let rest = line[digits..].trim_start();
+
+        // Only a number in front of the checkbox refers to an existing item.
+        let number = if rest.starts_with('[') {
+            let number = line[..digits].parse::<usize>().ok();
+            line = rest;
+            number
+        } else {
+            None
+        };
+
+        let is_finished = if line.starts_with("[x]") || line.starts_with("[X]") {
+            line = &line[3..];
+            true
+        } else {
+            if line.starts_with("[ ]") {
+                line = &line[3..];
+            }
+            false
+        };
+
+        let fields = split_fields(line.trim_start());
+        let field = |index: usize| {
+            fields
+                .get(index)
+                .map_or(String::new(), |field| unescape(field.trim()))
+        };
+        let item_text = field(0);
+        let deadline = field(1);
+        let tags = parse_tags(&fields[2.min(fields.len())..].join(" | "));
+
+        if item_text.is_empty() {
+            continue;
+        }
+
+        let item = number
+            .and_then(|number| number.checked_sub(1))
+            .and_then(|index| items.get_mut(index))
+            .and_then(Option::take);
+
+        let item = match item {
+            Some(mut item) => {
+                item.text = item_text;
+                item.set_finished(is_finished);
+                item.set_expire_datetime(deadline);
+                item
+            }
+            None => ItemState::new(0, item_text, deadline, is_finished, false),
+        };
+
+        new_items.push(ItemState { tags, ..item });
+    }
+
+    new_items
+}
+
+// Creates the file only if nothing, not even a symlink, is at `path` yet,
+// readable by the user alone, so that others sharing the temporary directory
+// can neither redirect nor read it.
+fn create_private(path: &Path) -> Result<File, Error> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    options
+        .open(path)
+        .map_err(|err| format_err!("could not create {}: {}", path.display(), err))
+}
+
+// Suspends the interface while the user edits `text` in `$VISUAL` or
+// `$EDITOR`, and returns the saved file.
+pub fn edit(text: &str, file_name: &str) -> Result<String, Error> {
+    let editor = env::var("VISUAL")
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .or_else(|| env::var("EDITOR").ok())
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+
+    let mut args = editor.split_whitespace();
+    let program = args.next().unwrap();
+
+    let path = env::temp_dir().join(format!("tdr-{}-{}", process::id(), file_name));
+    create_private(&path)?.write_all(text.as_bytes())?;
+
+    pause_ui_events();
+    cleanup_terminal();
+
+    let status = Command::new(program).args(args).arg(&path).status();
+
+    setup_terminal();
+    resume_ui_events();
+
+    let result = match status {
+        Ok(status) if status.success() => fs::read_to_string(&path).map_err(Error::from),
+        Ok(status) => Err(format_err!("{} exited with {}", program, status)),
+        Err(err) => Err(format_err!("could not run {}: {}", program, err)),
+    };
+
+    let _ = fs::remove_file(&path);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tagged_item() -> ItemState {
+        let mut item = ItemState::new(0, String::from("a | b"), String::new(), false, false);
+        item.tags = vec![
+            String::from("a|b"),
+            String::from("two words"),
+            String::from("back\\slash"),
+        ];
+        item.notes = String::from("first\nsecond");
+        item
+    }
+
+    #[test]
+    fn item_round_trips_through_the_editor_format() {
+        let item = tagged_item();
+        let text = format_item(&item);
+
+        // A second pass over the saved text must not pile up escapes.
+        let mut parsed = ItemState::new(0, String::new(), String::new(), false, false);
+        parse_item(&text, &mut parsed).unwrap();
+        parse_item(&format_item(&parsed), &mut parsed).unwrap();
+
+        assert_eq!(parsed.text, item.text);
+        assert_eq!(parsed.tags, item.tags);
+        assert_eq!(parsed.notes, item.notes);
+    }
+
+    fn workspace_items() -> Vec<ItemState> {
+        vec![
+            tagged_item(),
+            ItemState::new(0, String::from("[x] c"), String::new(), true, false),
+            ItemState::new(0, String::from("#d \\"), String::new(), false, false),
+        ]
+    }
+
+    #[test]
+    fn workspace_round_trips_through_the_editor_format() {
+        let items = workspace_items();
+        let text = format_workspace("title", items.iter());
+
+        let mut originals: Vec<_> = workspace_items().into_iter().map(Some).collect();
+        let parsed = parse_workspace(&text, &mut originals);
+
+        assert_eq!(parsed.len(), items.len());
+        for (parsed, item) in parsed.iter().zip(items.iter()) {
+            assert_eq!(parsed.text, item.text);
+            assert_eq!(parsed.tags, item.tags);
+            assert_eq!(parsed.is_finished, item.is_finished);
+        }
+    }
+}
@@ -1,14 +1,36 @@
 use std::fs;
+use std::time::Duration;
 
-use app::ScrollDirection;
+use chrono::{DateTime, Duration as ChronoDuration, Local};
 use crossbeam_channel::Sender;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 
 use crate::app::{self, Mode};
-use crate::cleanup_terminal;
+use crate::command::{self, Command};
+use crate::config::Autosave;
+use crate::editor;
+use crate::export;
+use crate::keymap::{Action, KeyChord, KeyLookup, KeymapMode};
+use crate::reminder;
+use crate::widget::{
+    agenda_entries, board_columns, items_due_on, parse_snooze, wip_limit, ArchivedItem,
+    ConfirmAction, HelpPage, ItemState, ItemStatus, PromptAction, SortMode, TrashEntry, TrashState,
+    TrashedItem, TrashedWorkspace, WorkspaceState, DATETIME_FORMAT, PRIORITY_NAMES,
+};
+use crate::{cleanup_terminal, theme, CONFIG, KEYMAP};
 
 use anyhow::{format_err, Error};
 
+fn quote(text: &str) -> String {
+    format!(
+        "\"{}\"",
+        text.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('\t', "\\t")
+    )
+}
+
 fn write_items(app: &mut app::App) -> Result<(), Error> {
     let query_path = dirs_next::home_dir()
         .ok_or_else(|| format_err!("could not get home directory"))?
@@ -23,7 +45,7 @@ fn write_items(app: &mut app::App) -> Result<(), Error> {
 
     query_text.push_str("workspace_title:\n");
     for workspace in app.workspaces.iter() {
-        query_text.push_str(&format!("    - {}\n", workspace.title));
+        query_text.push_str(&format!("    - {}\n", quote(&workspace.title)));
     }
 
     query_text.push_str("workspace_num_of_item:\n");
@@ -31,383 +53,2054 @@ fn write_items(app: &mut app::App) -> Result<(), Error> {
         query_text.push_str(&format!("    - {}\n", workspace.num_of_item));
     }
 
+    query_text.push_str("workspace_sort:\n");
+    for workspace in app.workspaces.iter() {
+        query_text.push_str(&format!("    - {}\n", workspace.sort.name()));
+    }
+
     query_text.push_str("item_slot:\n");
     for item in app.items.iter().flat_map(|r| r.iter()) {
         query_text.push_str(&format!("    - {}\n", item.slot));
     }
 
-    query_text.push_str("item_text:\n");
-    for item in app.items.iter().flat_map(|r| r.iter()) {
-        query_text.push_str(&format!("    - {}\n", item.text));
+    query_text.push_str("item_text:\n");
+    for item in app.items.iter().flat_map(|r| r.iter()) {
+        query_text.push_str(&format!("    - {}\n", quote(&item.text)));
+    }
+
+    query_text.push_str("item_expire_datetime_string:\n");
+    for item in app.items.iter().flat_map(|r| r.iter()) {
+        query_text.push_str(&format!("    - {}\n", item.expire_datetime_string));
+    }
+
+    query_text.push_str("item_is_finished:\n");
+    for item in app.items.iter().flat_map(|r| r.iter()) {
+        query_text.push_str(&format!("    - {}\n", item.is_finished));
+    }
+
+    query_text.push_str("item_notes:\n");
+    for item in app.items.iter().flat_map(|r| r.iter()) {
+        query_text.push_str(&format!("    - {}\n", quote(&item.notes)));
+    }
+
+    query_text.push_str("item_tags:\n");
+    for item in app.items.iter().flat_map(|r| r.iter()) {
+        query_text.push_str(&format!(
+            "    - [{}]\n",
+            item.tags
+                .iter()
+                .map(|tag| quote(tag))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    query_text.push_str("item_priority:\n");
+    for item in app.items.iter().flat_map(|r| r.iter()) {
+        query_text.push_str(&format!("    - {}\n", item.priority));
+    }
+
+    query_text.push_str("item_created:\n");
+    for item in app.items.iter().flat_map(|r| r.iter()) {
+        query_text.push_str(&format!(
+            "    - {}\n",
+            quote(
+                &item
+                    .created
+                    .map(|created| created.format(DATETIME_FORMAT).to_string())
+                    .unwrap_or_default()
+            )
+        ));
+    }
+
+    query_text.push_str("item_completed:\n");
+    for item in app.items.iter().flat_map(|r| r.iter()) {
+        query_text.push_str(&format!(
+            "    - {}\n",
+            quote(
+                &item
+                    .completed
+                    .map(|completed| completed.format(DATETIME_FORMAT).to_string())
+                    .unwrap_or_default()
+            )
+        ));
+    }
+
+    query_text.push_str("item_status:\n");
+    for item in app.items.iter().flat_map(|r| r.iter()) {
+        query_text.push_str(&format!("    - {}\n", item.status.name()));
+    }
+
+    query_text.push_str("item_reminders:\n");
+    for item in app.items.iter().flat_map(|r| r.iter()) {
+        query_text.push_str(&format!(
+            "    - {}\n",
+            quote(&reminder::format_offsets(&item.reminders))
+        ));
+    }
+
+    query_text.push_str("item_snoozed_until:\n");
+    for item in app.items.iter().flat_map(|r| r.iter()) {
+        query_text.push_str(&format!(
+            "    - {}\n",
+            quote(
+                &item
+                    .snoozed_until
+                    .map(|until| until.format(DATETIME_FORMAT).to_string())
+                    .unwrap_or_default()
+            )
+        ));
+    }
+
+    query_text.push_str("item_deferrals:\n");
+    for item in app.items.iter().flat_map(|r| r.iter()) {
+        query_text.push_str(&format!(
+            "    - [{}]\n",
+            item.deferrals
+                .iter()
+                .map(|deferral| quote(&deferral.format(DATETIME_FORMAT).to_string()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    query_text.push_str("archive:\n");
+    write_stored_items(
+        &mut query_text,
+        "    ",
+        app.archived
+            .iter()
+            .map(|entry| (entry.workspace.as_str(), entry.archived, &entry.item)),
+    );
+
+    query_text.push_str("trash:\n");
+    write_stored_items(
+        &mut query_text,
+        "    ",
+        app.trashed_items
+            .iter()
+            .map(|entry| (entry.workspace.as_str(), entry.deleted, &entry.item)),
+    );
+
+    query_text.push_str("trash_workspaces:\n");
+    for workspace in app.trashed_workspaces.iter() {
+        query_text.push_str(&format!("    - title: {}\n", quote(&workspace.title)));
+        query_text.push_str(&format!("      sort: {}\n", workspace.sort.name()));
+        query_text.push_str(&format!(
+            "      removed: {}\n",
+            format_datetime(Some(workspace.deleted))
+        ));
+
+        if workspace.items.is_empty() {
+            query_text.push_str("      items: []\n");
+        } else {
+            query_text.push_str("      items:\n");
+            write_stored_items(
+                &mut query_text,
+                "          ",
+                workspace
+                    .items
+                    .iter()
+                    .map(|item| (workspace.title.as_str(), workspace.deleted, item)),
+            );
+        }
+    }
+
+    fs::write(&query_path, query_text)?;
+
+    Ok(())
+}
+
+fn format_datetime(datetime: Option<DateTime<Local>>) -> String {
+    quote(
+        &datetime
+            .map(|datetime| datetime.format(DATETIME_FORMAT).to_string())
+            .unwrap_or_default(),
+    )
+}
+
+// Writes items kept outside the workspaces as a list of maps at `indent`, each
+// with the title of its workspace and when it was removed.
+fn write_stored_items<'a>(
+    query_text: &mut String,
+    indent: &str,
+    items: impl Iterator<Item = (&'a str, DateTime<Local>, &'a ItemState)>,
+) {
+    for (workspace, removed, item) in items {
+        let fields = [
+            ("workspace", quote(workspace)),
+            ("removed", format_datetime(Some(removed))),
+            ("text", quote(&item.text)),
+            ("deadline", quote(&item.expire_datetime_string)),
+            ("is_finished", item.is_finished.to_string()),
+            ("notes", quote(&item.notes)),
+            (
+                "tags",
+                format!(
+                    "[{}]",
+                    item.tags
+                        .iter()
+                        .map(|tag| quote(tag))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ),
+            ("priority", item.priority.to_string()),
+            ("created", format_datetime(item.created)),
+            ("completed", format_datetime(item.completed)),
+            ("status", item.status.name().to_string()),
+            (
+                "reminders",
+                quote(&reminder::format_offsets(&item.reminders)),
+            ),
+            ("snoozed_until", format_datetime(item.snoozed_until)),
+            (
+                "deferrals",
+                format!(
+                    "[{}]",
+                    item.deferrals
+                        .iter()
+                        .map(|&deferral| format_datetime(Some(deferral)))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ),
+        ];
+
+        for (position, (key, value)) in fields.iter().enumerate() {
+            query_text.push_str(&format!(
+                "{}{} {}: {}\n",
+                indent,
+                if position == 0 { "-" } else { " " },
+                key,
+                value
+            ));
+        }
+    }
+}
+
+fn save(app: &mut app::App) -> bool {
+    match write_items(app) {
+        Ok(()) => {
+            app.is_modified = false;
+            app.modified_at = None;
+            app.set_message("Saved ~/.todo.yml".to_string(), false);
+            true
+        }
+        Err(err) => {
+            app.set_message(format!("Could not save: {}", err), true);
+            false
+        }
+    }
+}
+
+fn delete_workspace(app: &mut app::App) {
+    if !app.workspaces.is_empty() {
+        let items = app.items.remove(app.current_workspace);
+        let workspace = app.workspaces.remove(app.current_workspace);
+
+        app.trashed_workspaces.push(TrashedWorkspace {
+            title: workspace.title,
+            sort: workspace.sort,
+            deleted: Local::now(),
+            items,
+        });
+
+        for workspace in app.workspaces.iter_mut() {
+            if workspace.slot > app.current_workspace {
+                workspace.slot -= 1;
+            }
+        }
+
+        if app.current_workspace != 0 {
+            app.current_workspace -= 1;
+        }
+
+        app.set_modified();
+    }
+}
+
+fn quit() {
+    cleanup_terminal();
+    std::process::exit(0);
+}
+
+fn save_and_quit(app: &mut app::App) {
+    if save(app) {
+        quit();
+    }
+}
+
+fn quit_without_saving(app: &mut app::App) {
+    if app.is_modified {
+        ask_confirm(
+            app,
+            ConfirmAction::Quit,
+            "Discard unsaved changes and quit?".to_string(),
+        );
+    } else {
+        quit();
+    }
+}
+
+fn ask_confirm(app: &mut app::App, action: ConfirmAction, message: String) {
+    if CONFIG.confirm {
+        app.confirm.ask(action, message);
+        app.previous_mode = app.mode;
+        app.mode = app::Mode::Confirm;
+    } else {
+        run_confirm_action(app, action);
+    }
+}
+
+fn run_confirm_action(app: &mut app::App, action: ConfirmAction) {
+    match action {
+        ConfirmAction::DeleteWorkspace => delete_workspace(app),
+        ConfirmAction::DeleteItems => {
+            let targets = app.targets();
+            delete_items(app, targets);
+        }
+        ConfirmAction::Quit => quit(),
+    }
+}
+
+fn handle_keys_confirm(key_event: KeyEvent, app: &mut app::App) {
+    let action = app.confirm.action;
+
+    app.confirm.reset();
+    app.mode = app.previous_mode;
+
+    if let (Some(Action::Submit), Some(action)) =
+        (read_prompt_action(KeymapMode::Confirm, key_event), action)
+    {
+        run_confirm_action(app, action);
+    }
+}
+
+fn handle_keys_add_workspace(key_event: KeyEvent, app: &mut app::App) {
+    match read_prompt_action(KeymapMode::Prompt, key_event) {
+        Some(Action::Submit) => {
+            if !app.add_workspace.input.is_empty() {
+                app.set_modified();
+
+                let workspace = app.add_workspace.enter(app.workspaces.len(), 0);
+
+                app.items.push(Vec::new());
+                app.workspaces.push(workspace);
+                app.current_workspace = app.workspaces.len() - 1;
+
+                app.add_workspace.reset();
+
+                app.mode = app.previous_mode;
+            } else {
+                app.mode = app.previous_mode;
+            }
+        }
+        Some(Action::Cancel) => {
+            app.add_workspace.reset();
+            app.mode = app.previous_mode;
+        }
+        _ => {
+            app.add_workspace
+                .handle_key(key_event.code, key_event.modifiers);
+        }
+    }
+}
+
+fn handle_keys_edit_workspace(key_event: KeyEvent, app: &mut app::App) {
+    match read_prompt_action(KeymapMode::Prompt, key_event) {
+        Some(Action::Submit) => {
+            app.set_modified();
+
+            app.edit_workspace
+                .enter(&mut app.workspaces[app.current_workspace]);
+
+            app.edit_workspace.reset();
+            app.mode = app.previous_mode;
+        }
+        Some(Action::Cancel) => {
+            app.edit_workspace.reset();
+            app.mode = app.previous_mode;
+        }
+        _ => {
+            app.edit_workspace
+                .handle_key(key_event.code, key_event.modifiers);
+        }
+    }
+}
+
+fn edit_item_in_editor(app: &mut app::App) {
+    let index = match app.selected_index() {
+        Some(index) => index,
+        None => return,
+    };
+
+    let text = editor::format_item(&app.items[app.current_workspace][index]);
+    let result = editor::edit(&text, "item.md");
+    app.needs_clear = true;
+
+    match result.and_then(|new_text| {
+        if new_text != text {
+            editor::parse_item(&new_text, &mut app.items[app.current_workspace][index])?;
+            app.set_modified();
+        }
+        Ok(())
+    }) {
+        Ok(()) => app.select_index(index),
+        Err(err) => app.set_message(format!("Edit failed: {}", err), true),
+    }
+}
+
+fn edit_workspace_in_editor(app: &mut app::App) {
+    let workspace = app.current_workspace;
+    let order = app.sorted_items_in(workspace);
+
+    let text = editor::format_workspace(
+        &app.workspaces[workspace].title,
+        order.iter().map(|&index| &app.items[workspace][index]),
+    );
+    let result = editor::edit(&text, "workspace.txt");
+    app.needs_clear = true;
+
+    match result {
+        Ok(new_text) if new_text != text => {
+            let mut items: Vec<_> = std::mem::take(&mut app.items[workspace])
+                .into_iter()
+                .map(Some)
+                .collect();
+            let mut items: Vec<_> = order.iter().map(|&index| items[index].take()).collect();
+
+            app.items[workspace] = editor::parse_workspace(&new_text, &mut items);
+            trash_items(app, workspace, items.into_iter().flatten());
+            app.renumber_items(workspace);
+            app.clear_selection();
+            app.current_item = 0;
+            app.set_modified();
+        }
+        Ok(_) => {}
+        Err(err) => app.set_message(format!("Edit failed: {}", err), true),
+    }
+}
+
+const MAX_COUNT: usize = 99_999;
+
+// Collects a count prefix and the chords of a multi-key binding, and returns
+// the action with its count once the keys pressed so far name one.
+fn read_action(
+    app: &mut app::App,
+    mode: KeymapMode,
+    key_event: KeyEvent,
+) -> Option<(Action, Option<usize>)> {
+    let chord = KeyChord::from_event(key_event);
+
+    if app.pending_keys.is_empty() && chord.modifiers == KeyModifiers::NONE {
+        if let KeyCode::Char(c @ '0'..='9') = chord.code {
+            if (c != '0' || app.count.is_some())
+                && KEYMAP.lookup(mode, &[chord]) == KeyLookup::Unbound
+            {
+                let digit = c.to_digit(10).unwrap() as usize;
+                app.count = Some((app.count.unwrap_or(0) * 10 + digit).min(MAX_COUNT));
+                return None;
+            }
+        }
+    }
+
+    app.pending_keys.push(chord);
+
+    match KEYMAP.lookup(mode, &app.pending_keys) {
+        KeyLookup::Pending => None,
+        lookup => {
+            app.pending_keys.clear();
+            let count = app.count.take();
+
+            match lookup {
+                KeyLookup::Action(action) => Some((action, count)),
+                _ => None,
+            }
+        }
+    }
+}
+
+// Looks up a single chord of a prompt. Keys without a binding are left to the
+// input, so counts and multi-key bindings do not apply here.
+fn read_prompt_action(mode: KeymapMode, key_event: KeyEvent) -> Option<Action> {
+    match KEYMAP.lookup(mode, &[KeyChord::from_event(key_event)]) {
+        KeyLookup::Action(action) => Some(action),
+        _ => None,
+    }
+}
+
+// The row a motion moves the selection of a view's list of `len` rows to, or
+// None for any other action.
+fn list_motion(selected: usize, len: usize, action: Action, count: Option<usize>) -> Option<usize> {
+    let n = count.unwrap_or(1).max(1);
+
+    let target = match action {
+        Action::ScrollDown => selected.saturating_add(n),
+        Action::ScrollUp => selected.saturating_sub(n),
+        Action::GoToTop => n - 1,
+        Action::GoToBottom => count.map_or(usize::MAX, |_| n - 1),
+        _ => return None,
+    };
+
+    Some(target.min(len.saturating_sub(1)))
+}
+
+// Cursor motions shared by the item and workspace lists. Returns false for
+// any other action.
+fn move_cursor(app: &mut app::App, mode: KeymapMode, action: Action, count: Option<usize>) -> bool {
+    let (row, len, scroll) = match mode {
+        KeymapMode::Item => (app.current_item, app.visible_items().len(), app.item_scroll),
+        KeymapMode::Workspace => (
+            app.current_workspace,
+            app.workspaces.len(),
+            app.workspace_scroll,
+        ),
+        _ => return false,
+    };
+
+    let n = count.unwrap_or(1).max(1);
+    let half_page = (scroll.height / 2).max(1);
+
+    let target = match action {
+        Action::ScrollDown => row.saturating_add(n),
+        Action::ScrollUp => row.saturating_sub(n),
+        Action::HalfPageDown => row.saturating_add(half_page),
+        Action::HalfPageUp => row.saturating_sub(half_page),
+        Action::GoToTop => n - 1,
+        Action::GoToBottom => count.map_or(usize::MAX, |_| n - 1),
+        Action::ScreenTop => scroll.offset.saturating_add(n - 1),
+        Action::ScreenMiddle => scroll.offset + scroll.height.saturating_sub(1) / 2,
+        Action::ScreenBottom => (scroll.offset + scroll.height).saturating_sub(n),
+        _ => return false,
+    };
+
+    if len > 0 {
+        match mode {
+            KeymapMode::Item => app.current_item = target.min(len - 1),
+            KeymapMode::Workspace => {
+                let target = target.min(len - 1);
+
+                // The items of the workspace show up next to the list.
+                if target != app.current_workspace {
+                    app.current_item = 0;
+                    app.current_workspace = target;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    true
+}
+
+// Moves the cursor to the `count`-th visible item after (or before) it for
+// which `wanted` holds. Stays put when there are not that many.
+fn jump_to_item(
+    app: &mut app::App,
+    forward: bool,
+    count: Option<usize>,
+    wanted: fn(&ItemState) -> bool,
+    name: &str,
+) {
+    let visible_items = app.visible_items();
+    let items = &app.items[app.current_workspace];
+
+    let rows: Vec<usize> = if forward {
+        (app.current_item + 1..visible_items.len()).collect()
+    } else {
+        (0..app.current_item.min(visible_items.len()))
+            .rev()
+            .collect()
+    };
+
+    let target = rows
+        .into_iter()
+        .filter(|&row| wanted(&items[visible_items[row]]))
+        .nth(count.unwrap_or(1).max(1) - 1);
+
+    match target {
+        Some(row) => app.current_item = row,
+        None => app.set_message(
+            format!(
+                "No {} item {}",
+                name,
+                if forward { "below" } else { "above" }
+            ),
+            false,
+        ),
+    }
+}
+
+fn is_late(item: &ItemState) -> bool {
+    item.is_late && !item.is_finished
+}
+
+fn is_due(item: &ItemState) -> bool {
+    item.has_expire_datetime && !item.is_late && !item.is_finished
+}
+
+fn handle_keys_display_workspace(key_event: KeyEvent, app: &mut app::App) {
+    if let Some((action, count)) = read_action(app, KeymapMode::Workspace, key_event) {
+        run_workspace_action(app, action, count);
+    }
+}
+
+fn run_workspace_action(app: &mut app::App, action: Action, count: Option<usize>) {
+    if move_cursor(app, KeymapMode::Workspace, action, count) {
+        return;
+    }
+
+    match action {
+        Action::OpenWorkspace => {
+            if !app.workspaces.is_empty() {
+                app.current_item = 0;
+                app.previous_mode = app.mode;
+                app.mode = app::Mode::DisplayItem;
+            }
+        }
+        // Unlike opening a workspace, keeps the item cursor where it was.
+        Action::SwitchPane => {
+            if !app.workspaces.is_empty() {
+                app.current_item = app
+                    .current_item
+                    .min(app.visible_items().len().saturating_sub(1));
+                app.previous_mode = app.mode;
+                app.mode = app::Mode::DisplayItem;
+            }
+        }
+        Action::AddWorkspace => {
+            app.previous_mode = app.mode;
+            app.mode = app::Mode::AddWorkspace;
+        }
+        Action::EditWorkspace => {
+            if !app.workspaces.is_empty() {
+                app.edit_workspace
+                    .open(&app.workspaces[app.current_workspace]);
+                app.previous_mode = app.mode;
+                app.mode = app::Mode::EditWorkspace;
+            }
+        }
+        Action::EditWorkspaceInEditor => {
+            if !app.workspaces.is_empty() {
+                edit_workspace_in_editor(app);
+            }
+        }
+        Action::DeleteWorkspace => {
+            if !app.workspaces.is_empty() {
+                let message = format!(
+                    "Move workspace '{}' and its {} items to the trash?",
+                    app.workspaces[app.current_workspace].title,
+                    app.items[app.current_workspace].len()
+                );
+                ask_confirm(app, ConfirmAction::DeleteWorkspace, message);
+            }
+        }
+        Action::Save => {
+            save(app);
+        }
+        Action::SaveAndQuit => save_and_quit(app),
+        Action::Quit => quit_without_saving(app),
+        Action::ShowAgenda => open_agenda(app),
+        Action::ShowCalendar => open_calendar(app),
+        Action::ShowDashboard => open_dashboard(app),
+        Action::ShowArchive => open_archive(app),
+        Action::ShowTrash => open_trash(app),
+        Action::CommandLine => open_command_line(app),
+        Action::Palette => open_palette(app, KeymapMode::Workspace),
+        Action::Help => {
+            app.help.open(HelpPage::Workspace);
+            app.previous_mode = app.mode;
+            app.mode = app::Mode::DisplayHelp;
+        }
+        _ => {}
+    }
+}
+
+fn handle_keys_add_item(key_event: KeyEvent, app: &mut app::App) {
+    match read_prompt_action(KeymapMode::ItemPrompt, key_event) {
+        Some(Action::Submit) => {
+            app.add_item.has_expire_datetime = false;
+
+            if !app.add_item.input.is_empty() {
+                app.set_modified();
+
+                let index = app.items[app.current_workspace].len();
+                let item = app.add_item.enter(index);
+
+                app.items[app.current_workspace].push(item);
+                app.select_index(index);
+
+                app.add_item.reset();
+                app.mode = app.previous_mode;
+            } else {
+                app.mode = app.previous_mode;
+            }
+        }
+        Some(Action::ToggleDeadlineInput) => {
+            app.add_item.has_expire_datetime = !app.add_item.has_expire_datetime;
+        }
+        Some(Action::Cancel) => {
+            app.add_item.reset();
+            app.mode = app.previous_mode;
+        }
+        _ => {
+            app.add_item.handle_key(key_event.code, key_event.modifiers);
+        }
+    }
+}
+
+fn handle_keys_edit_item(key_event: KeyEvent, app: &mut app::App) {
+    match read_prompt_action(KeymapMode::ItemPrompt, key_event) {
+        Some(Action::Submit) => {
+            let index = match app.selected_index() {
+                Some(index) => index,
+                None => {
+                    app.edit_item.reset();
+                    app.mode = app.previous_mode;
+                    return;
+                }
+            };
+
+            app.set_modified();
+            app.edit_item.has_expire_datetime = false;
+
+            app.edit_item
+                .enter(&mut app.items[app.current_workspace][index]);
+            app.select_index(index);
+
+            app.edit_item.reset();
+            app.mode = app.previous_mode;
+        }
+        Some(Action::ToggleDeadlineInput) => {
+            app.edit_item.has_expire_datetime = !app.edit_item.has_expire_datetime;
+        }
+        Some(Action::Cancel) => {
+            app.edit_item.reset();
+            app.mode = app.previous_mode;
+        }
+        _ => {
+            app.edit_item
+                .handle_key(key_event.code, key_event.modifiers);
+        }
+    }
+}
+
+// Moves the cursor to the next (or previous) search hit, crossing into other
+// workspaces when searching all of them.
+fn step_match(app: &mut app::App, forward: bool, wrap: bool) {
+    let workspaces: Vec<usize> = if app.search.all_workspaces {
+        (0..app.workspaces.len()).collect()
+    } else {
+        vec![app.current_workspace]
+    };
+
+    let hits: Vec<(usize, usize)> = workspaces
+        .into_iter()
+        .flat_map(|workspace| {
+            (0..app.visible_items_in(workspace).len()).map(move |row| (workspace, row))
+        })
+        .collect();
+
+    if hits.is_empty() {
+        return;
+    }
+
+    let current = hits
+        .iter()
+        .position(|&hit| hit == (app.current_workspace, app.current_item))
+        .unwrap_or(0);
+
+    let next = if forward {
+        if current + 1 < hits.len() {
+            current + 1
+        } else if wrap {
+            0
+        } else {
+            current
+        }
+    } else if current > 0 {
+        current - 1
+    } else if wrap {
+        hits.len() - 1
+    } else {
+        current
+    };
+
+    let (workspace, row) = hits[next];
+    app.current_workspace = workspace;
+    app.current_item = row;
+}
+
+fn update_search(app: &mut app::App) {
+    let workspaces: Vec<usize> = if app.search.all_workspaces {
+        (0..app.workspaces.len()).collect()
+    } else {
+        vec![app.current_workspace]
+    };
+
+    app.search.num_of_match = if app.search.is_active() {
+        workspaces
+            .into_iter()
+            .map(|workspace| app.visible_items_in(workspace).len())
+            .sum()
+    } else {
+        0
+    };
+
+    app.current_item = 0;
+    app.item_scroll.offset = 0;
+
+    if app.search.all_workspaces && app.visible_items().is_empty() {
+        step_match(app, true, false);
+    }
+}
+
+fn handle_keys_search(key_event: KeyEvent, app: &mut app::App) {
+    match read_prompt_action(KeymapMode::Search, key_event) {
+        Some(Action::Submit) => {
+            app.mode = app.previous_mode;
+        }
+        Some(Action::ToggleAllWorkspaces) => {
+            app.search.all_workspaces = !app.search.all_workspaces;
+            update_search(app);
+        }
+        Some(Action::Cancel) => {
+            app.search.reset();
+            update_search(app);
+            app.mode = app.previous_mode;
+        }
+        _ => {
+            let query = app.search.input.text().to_string();
+
+            if app.search.handle_key(key_event.code, key_event.modifiers)
+                && app.search.input.text() != query
+            {
+                update_search(app);
+            }
+        }
+    }
+}
+
+// Swaps the selected item with its visible neighbour in the storage order.
+fn move_selected_item(app: &mut app::App, forward: bool) {
+    // Sorting reorders the storage but keeps the cursor on the same row, so
+    // the selected item is looked up only afterwards.
+    if app.workspaces[app.current_workspace].sort != SortMode::Manual {
+        app.apply_sort(app.current_workspace);
+    }
+
+    let index = match app.selected_index() {
+        Some(index) => index,
+        None => return,
+    };
+
+    let visible_items = app.visible_items();
+    let row = match visible_items.iter().position(|&i| i == index) {
+        Some(row) => row,
+        None => return,
+    };
+
+    let other_row = if forward {
+        row + 1
+    } else if row > 0 {
+        row - 1
+    } else {
+        return;
+    };
+
+    if let Some(&other) = visible_items.get(other_row) {
+        app.items[app.current_workspace].swap(index, other);
+        app.renumber_items(app.current_workspace);
+        app.current_item = other_row;
+        app.set_modified();
+    }
+}
+
+fn move_items_to_workspace(
+    app: &mut app::App,
+    workspace: usize,
+    mut indices: Vec<usize>,
+    target: usize,
+) {
+    if workspace == target {
+        return;
+    }
+
+    indices.sort_unstable();
+
+    let mut items = Vec::new();
+    for &index in indices.iter().rev() {
+        items.push(app.items[workspace].remove(index));
+    }
+    app.renumber_items(workspace);
+
+    for mut item in items.into_iter().rev() {
+        item.slot = app.items[target].len();
+        item.is_marked = false;
+        app.items[target].push(item);
+    }
+
+    app.set_modified();
+}
+
+// Moves `indices` of `workspace` to the archive.
+fn archive_items(app: &mut app::App, workspace: usize, mut indices: Vec<usize>) {
+    indices.sort_unstable();
+
+    let now = Local::now();
+    let title = app.workspaces[workspace].title.clone();
+
+    for &index in indices.iter().rev() {
+        let mut item = app.items[workspace].remove(index);
+        item.is_marked = false;
+        item.is_selected = false;
+
+        app.archived.push(ArchivedItem {
+            workspace: title.clone(),
+            archived: now,
+            item,
+        });
+    }
+
+    if !indices.is_empty() {
+        app.renumber_items(workspace);
+        app.workspaces[workspace].num_of_item = app.items[workspace].len();
+        app.set_modified();
+    }
+
+    app.clear_selection();
+    app.current_item = app
+        .current_item
+        .min(app.visible_items().len().saturating_sub(1));
+}
+
+fn archive_targets(app: &mut app::App, indices: Vec<usize>) {
+    let num_of_item = indices.len();
+
+    archive_items(app, app.current_workspace, indices);
+    app.set_message(
+        format!(
+            "Archived {} item{}",
+            num_of_item,
+            if num_of_item == 1 { "" } else { "s" }
+        ),
+        false,
+    );
+}
+
+fn archive_finished(app: &mut app::App) {
+    if app.workspaces.is_empty() {
+        return;
+    }
+
+    let finished = app.items[app.current_workspace]
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.is_finished)
+        .map(|(index, _)| index)
+        .collect();
+
+    archive_targets(app, finished);
+}
+
+// Archives the items finished more than `archive_after_days` ago, on startup.
+pub fn archive_expired(app: &mut app::App) {
+    let days = match CONFIG.archive_after_days {
+        Some(days) => days,
+        None => return,
+    };
+    let cutoff = Local::now() - ChronoDuration::days(days as i64);
+    let mut num_of_item = 0;
+
+    for workspace in 0..app.workspaces.len() {
+        let expired: Vec<usize> = app.items[workspace]
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| {
+                item.is_finished && item.completed.map_or(false, |completed| completed < cutoff)
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        num_of_item += expired.len();
+        archive_items(app, workspace, expired);
+    }
+
+    if num_of_item > 0 {
+        app.set_message(
+            format!(
+                "Archived {} item{} finished over {} day{} ago",
+                num_of_item,
+                if num_of_item == 1 { "" } else { "s" },
+                days,
+                if days == 1 { "" } else { "s" }
+            ),
+            false,
+        );
+    }
+}
+
+fn push_workspace(app: &mut app::App, title: String, sort: SortMode) -> usize {
+    let mut workspace = WorkspaceState::new(app.workspaces.len(), title, 0, false);
+    workspace.sort = sort;
+
+    app.workspaces.push(workspace);
+    app.items.push(Vec::new());
+    app.workspaces.len() - 1
+}
+
+// Puts a removed item back at the end of the workspace titled `title`, which
+// is created again if it was deleted since.
+fn restore_item(app: &mut app::App, title: String, mut item: ItemState) {
+    let workspace = match app
+        .workspaces
+        .iter()
+        .position(|workspace| workspace.title == title)
+    {
+        Some(workspace) => workspace,
+        None => push_workspace(app, title.clone(), SortMode::Manual),
+    };
+
+    item.slot = app.items[workspace].len();
+    app.items[workspace].push(item);
+    app.workspaces[workspace].num_of_item = app.items[workspace].len();
+    app.set_modified();
+
+    app.set_message(format!("Restored item to '{}'", title), false);
+}
+
+fn restore_archived(app: &mut app::App, index: usize) {
+    let entry = app.archived.remove(index);
+
+    restore_item(app, entry.workspace, entry.item);
+}
+
+// Takes an item or a whole workspace out of the trash. A workspace comes back
+// at the end of the list, even when another one has its title by now.
+fn restore_trashed(app: &mut app::App, entry: TrashEntry) {
+    match entry {
+        TrashEntry::Item(index) => {
+            let trashed = app.trashed_items.remove(index);
+
+            restore_item(app, trashed.workspace, trashed.item);
+        }
+        TrashEntry::Workspace(index) => {
+            let trashed = app.trashed_workspaces.remove(index);
+            let workspace = push_workspace(app, trashed.title.clone(), trashed.sort);
+
+            app.items[workspace] = trashed.items;
+            app.renumber_items(workspace);
+            app.workspaces[workspace].num_of_item = app.items[workspace].len();
+            app.set_modified();
+
+            app.set_message(format!("Restored workspace '{}'", trashed.title), false);
+        }
+    }
+}
+
+// Drops what has been in the trash longer than `trash_retention_days`, on
+// startup. Zero keeps everything.
+pub fn purge_trash(app: &mut app::App) {
+    if CONFIG.trash_retention_days == 0 {
+        return;
+    }
+
+    let cutoff = Local::now() - ChronoDuration::days(CONFIG.trash_retention_days as i64);
+    let num_of_entry = app.trashed_items.len() + app.trashed_workspaces.len();
+
+    app.trashed_items
+        .retain(|trashed| trashed.deleted >= cutoff);
+    app.trashed_workspaces
+        .retain(|trashed| trashed.deleted >= cutoff);
+
+    if app.trashed_items.len() + app.trashed_workspaces.len() < num_of_entry {
+        app.set_modified();
+    }
+}
+
+// Moves items of `workspace` that were taken out of it to the trash.
+fn trash_items(app: &mut app::App, workspace: usize, items: impl Iterator<Item = ItemState>) {
+    let now = Local::now();
+    let title = app.workspaces[workspace].title.clone();
+
+    for mut item in items {
+        item.is_marked = false;
+        item.is_selected = false;
+
+        app.trashed_items.push(TrashedItem {
+            workspace: title.clone(),
+            deleted: now,
+            item,
+        });
+    }
+}
+
+// Moves `indices` of the current workspace to `target` and reports it.
+// Moving to the workspace the items are already in leaves them, and the
+// selection, as they were.
+fn move_targets(app: &mut app::App, indices: Vec<usize>, target: usize) {
+    if target == app.current_workspace {
+        app.set_message(
+            format!("Already in '{}'", app.workspaces[target].title),
+            false,
+        );
+        return;
+    }
+
+    let num_of_item = indices.len();
+
+    move_items_to_workspace(app, app.current_workspace, indices, target);
+    app.set_message(
+        format!(
+            "Moved {} item{} to '{}'",
+            num_of_item,
+            if num_of_item == 1 { "" } else { "s" },
+            app.workspaces[target].title
+        ),
+        false,
+    );
+
+    app.clear_selection();
+    app.current_item = app
+        .current_item
+        .min(app.visible_items().len().saturating_sub(1));
+}
+
+fn delete_items(app: &mut app::App, mut indices: Vec<usize>) {
+    indices.sort_unstable();
+
+    let mut items = Vec::new();
+    for &index in indices.iter().rev() {
+        items.push(app.items[app.current_workspace].remove(index));
+    }
+    trash_items(app, app.current_workspace, items.into_iter().rev());
+
+    if !indices.is_empty() {
+        app.renumber_items(app.current_workspace);
+        app.set_modified();
+        app.set_message(
+            format!(
+                "Moved {} item{} to the trash",
+                indices.len(),
+                if indices.len() == 1 { "" } else { "s" }
+            ),
+            false,
+        );
+    }
+
+    if indices.len() == 1 && app.current_item != 0 {
+        app.current_item -= 1;
+    }
+
+    app.clear_selection();
+    app.current_item = app
+        .current_item
+        .min(app.visible_items().len().saturating_sub(1));
+}
+
+fn apply_prompt(app: &mut app::App, action: PromptAction, input: String) {
+    let targets = app.targets();
+
+    let priority = if action == PromptAction::SetPriority {
+        match PRIORITY_NAMES
+            .iter()
+            .position(|&name| name == input.trim())
+            .or_else(|| input.trim().parse().ok())
+        {
+            Some(priority) if priority < PRIORITY_NAMES.len() => priority,
+            _ => {
+                app.set_message(format!("Unknown priority '{}'", input), true);
+                return;
+            }
+        }
+    } else {
+        0
+    };
+
+    let reminders = if action == PromptAction::SetReminders {
+        match reminder::parse_offsets(&input) {
+            Ok(reminders) => reminders,
+            Err(err) => {
+                app.set_message(format!("Could not set reminders: {}", err), true);
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
+    let is_snooze = action == PromptAction::Snooze || action == PromptAction::SnoozeAndDefer;
+    let snoozed_until = if is_snooze && !input.trim().is_empty() {
+        match parse_snooze(&input) {
+            Some(until) => Some(until),
+            None => {
+                app.set_message(format!("Unknown snooze time '{}'", input), true);
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
+    let tag = input.trim().trim_start_matches('#').to_string();
+    if action == PromptAction::AddTag && tag.contains(char::is_whitespace) {
+        app.set_message(format!("Tags can't contain spaces: '{}'", tag), true);
+        return;
+    }
+
+    for &index in targets.iter() {
+        let item = &mut app.items[app.current_workspace][index];
+
+        match action {
+            PromptAction::SetDeadline => item.set_expire_datetime(input.trim().to_string()),
+            PromptAction::AddTag => {
+                if !tag.is_empty() && !item.tags.contains(&tag) {
+                    item.tags.push(tag.clone());
+                }
+            }
+            PromptAction::SetPriority => item.priority = priority,
+            PromptAction::SetReminders => item.reminders = reminders.clone(),
+            PromptAction::Snooze | PromptAction::SnoozeAndDefer => match snoozed_until {
+                Some(until) => item.snooze(until, action == PromptAction::SnoozeAndDefer),
+                None => item.snoozed_until = None,
+            },
+        }
+    }
+
+    if !targets.is_empty() {
+        app.set_modified();
+    }
+
+    if is_snooze {
+        if let Some(until) = snoozed_until {
+            app.set_message(
+                format!(
+                    "Snoozed {} item{} until {}",
+                    targets.len(),
+                    if targets.len() == 1 { "" } else { "s" },
+                    until.format("%Y-%m-%d %H:%M")
+                ),
+                false,
+            );
+        }
+        app.current_item = app
+            .current_item
+            .min(app.visible_items().len().saturating_sub(1));
+    }
+
+    app.clear_selection();
+}
+
+fn handle_keys_prompt(key_event: KeyEvent, app: &mut app::App) {
+    match read_prompt_action(KeymapMode::Prompt, key_event) {
+        Some(Action::Submit) => {
+            let input = app.prompt.input.text().to_string();
+
+            if let Some(action) = app.prompt.action {
+                apply_prompt(app, action, input);
+            }
+
+            app.prompt.reset();
+            app.mode = app.previous_mode;
+        }
+        Some(Action::Cancel) => {
+            app.prompt.reset();
+            app.mode = app.previous_mode;
+        }
+        _ => {
+            app.prompt.handle_key(key_event.code, key_event.modifiers);
+        }
+    }
+}
+
+fn open_prompt(app: &mut app::App, action: PromptAction) {
+    if !app.targets().is_empty() {
+        app.prompt.open(action);
+        app.previous_mode = app.mode;
+        app.mode = app::Mode::Prompt;
+    }
+}
+
+fn open_command_line(app: &mut app::App) {
+    app.command_line.reset();
+    app.previous_mode = app.mode;
+    app.mode = app::Mode::CommandLine;
+}
+
+fn open_palette(app: &mut app::App, mode: KeymapMode) {
+    app.palette.open(mode);
+    app.previous_mode = app.mode;
+    app.mode = app::Mode::Palette;
+}
+
+fn open_agenda(app: &mut app::App) {
+    app.previous_mode = app.mode;
+    app.mode = app::Mode::Agenda;
+}
+
+// Moves the cursor to an item, in whichever workspace it is. A search that
+// hides the item is cleared, and snoozed items are shown if it is snoozed.
+fn show_item(app: &mut app::App, workspace: usize, index: usize) {
+    if workspace != app.current_workspace {
+        app.clear_selection();
+        app.current_workspace = workspace;
+    }
+
+    if app.items[workspace][index].is_snoozed() {
+        app.show_snoozed = true;
+    }
+
+    if !app.visible_items().contains(&index) {
+        app.search.reset();
+    }
+
+    app.select_index(index);
+}
+
+fn open_trash(app: &mut app::App) {
+    app.trash.selected = 0;
+    app.previous_mode = app.mode;
+    app.mode = app::Mode::Trash;
+}
+
+fn handle_keys_trash(key_event: KeyEvent, app: &mut app::App) {
+    let (action, count) = match read_action(app, KeymapMode::Trash, key_event) {
+        Some(action) => action,
+        None => return,
+    };
+    let entries = TrashState::entries(&app.trashed_items, &app.trashed_workspaces);
+
+    if let Some(selected) = list_motion(app.trash.selected, entries.len(), action, count) {
+        app.trash.selected = selected;
+        return;
+    }
+
+    match action {
+        Action::Restore => {
+            if let Some(&entry) = entries.get(app.trash.selected) {
+                restore_trashed(app, entry);
+            }
+        }
+        Action::Close => close_view(app),
+        _ => {}
+    }
+}
+
+fn open_archive(app: &mut app::App) {
+    app.archive.selected = 0;
+    app.previous_mode = app.mode;
+    app.mode = app::Mode::Archive;
+}
+
+fn handle_keys_archive(key_event: KeyEvent, app: &mut app::App) {
+    if app.archive.is_searching {
+        match read_prompt_action(KeymapMode::Prompt, key_event) {
+            Some(Action::Submit) => app.archive.is_searching = false,
+            Some(Action::Cancel) => {
+                app.archive.search.reset();
+                app.archive.is_searching = false;
+            }
+            _ => {
+                app.archive
+                    .search
+                    .handle_key(key_event.code, key_event.modifiers);
+                app.archive.selected = 0;
+            }
+        }
+        return;
+    }
+
+    let (action, count) = match read_action(app, KeymapMode::Archive, key_event) {
+        Some(action) => action,
+        None => return,
+    };
+    let entries = app.archive.entries(&app.archived);
+
+    if let Some(selected) = list_motion(app.archive.selected, entries.len(), action, count) {
+        app.archive.selected = selected;
+        return;
+    }
+
+    match action {
+        Action::Search => app.archive.is_searching = true,
+        Action::Restore => {
+            if let Some(&index) = entries.get(app.archive.selected) {
+                restore_archived(app, index);
+            }
+        }
+        // A search is cleared before the archive is left.
+        Action::Close if app.archive.search.is_active() => {
+            app.archive.search.reset();
+            app.archive.selected = 0;
+        }
+        Action::Close => close_view(app),
+        _ => {}
+    }
+}
+
+fn handle_keys_agenda(key_event: KeyEvent, app: &mut app::App) {
+    let (action, count) = match read_action(app, KeymapMode::Agenda, key_event) {
+        Some(action) => action,
+        None => return,
+    };
+    let entries = agenda_entries(&app.items, app.show_snoozed);
+    let entry = entries.get(app.agenda.selected).copied();
+
+    if let Some(selected) = list_motion(app.agenda.selected, entries.len(), action, count) {
+        app.agenda.selected = selected;
+        return;
+    }
+
+    match action {
+        Action::ToggleFinished => {
+            if let Some(entry) = entry {
+                let item = &mut app.items[entry.workspace][entry.index];
+                item.set_finished(!item.is_finished);
+                app.set_modified();
+            }
+        }
+        Action::EditItem => {
+            if let Some(entry) = entry {
+                show_item(app, entry.workspace, entry.index);
+                app.edit_item.open(&app.items[entry.workspace][entry.index]);
+                app.previous_mode = app::Mode::Agenda;
+                app.mode = app::Mode::EditItem;
+            }
+        }
+        Action::GoToItem => {
+            if let Some(entry) = entry {
+                show_item(app, entry.workspace, entry.index);
+                app.mode = app::Mode::DisplayItem;
+            }
+        }
+        Action::Close => close_view(app),
+        _ => {}
+    }
+}
+
+fn open_calendar(app: &mut app::App) {
+    app.calendar.open();
+    app.previous_mode = app.mode;
+    app.mode = app::Mode::Calendar;
+}
+
+fn handle_keys_calendar(key_event: KeyEvent, app: &mut app::App) {
+    if app.calendar.is_listing {
+        handle_keys_calendar_day(key_event, app);
+        return;
     }
 
-    query_text.push_str("item_expire_datetime_string:\n");
-    for item in app.items.iter().flat_map(|r| r.iter()) {
-        query_text.push_str(&format!("    - {}\n", item.expire_datetime_string));
+    let action = match read_action(app, KeymapMode::Calendar, key_event) {
+        Some((action, _count)) => action,
+        None => return,
+    };
+
+    match action {
+        Action::PreviousDay => app.calendar.move_days(-1),
+        Action::NextDay => app.calendar.move_days(1),
+        Action::NextWeek => app.calendar.move_days(7),
+        Action::PreviousWeek => app.calendar.move_days(-7),
+        Action::NextMonth => app.calendar.move_months(1),
+        Action::PreviousMonth => app.calendar.move_months(-1),
+        Action::GoToToday => app.calendar.open(),
+        Action::ListDay => app.calendar.list(),
+        Action::Close => close_view(app),
+        _ => {}
     }
+}
 
-    query_text.push_str("item_is_finished:\n");
-    for item in app.items.iter().flat_map(|r| r.iter()) {
-        query_text.push_str(&format!("    - {}\n", item.is_finished));
+// Keys of the list of items due on the selected day.
+fn handle_keys_calendar_day(key_event: KeyEvent, app: &mut app::App) {
+    let (action, count) = match read_action(app, KeymapMode::CalendarDay, key_event) {
+        Some(action) => action,
+        None => return,
+    };
+    let due = items_due_on(&app.items, app.calendar.date, app.show_snoozed);
+    let entry = due.get(app.calendar.selected).copied();
+
+    if let Some(selected) = list_motion(app.calendar.selected, due.len(), action, count) {
+        app.calendar.selected = selected;
+        return;
     }
 
-    let _ = fs::write(&query_path, query_text);
+    match action {
+        Action::ToggleFinished => {
+            if let Some((workspace, index)) = entry {
+                let item = &mut app.items[workspace][index];
+                item.set_finished(!item.is_finished);
+                app.set_modified();
+            }
+        }
+        Action::EditItem => {
+            if let Some((workspace, index)) = entry {
+                show_item(app, workspace, index);
+                app.edit_item.open(&app.items[workspace][index]);
+                app.previous_mode = app::Mode::Calendar;
+                app.mode = app::Mode::EditItem;
+            }
+        }
+        Action::GoToItem => {
+            if let Some((workspace, index)) = entry {
+                show_item(app, workspace, index);
+                app.mode = app::Mode::DisplayItem;
+            }
+        }
+        Action::Close => app.calendar.is_listing = false,
+        _ => {}
+    }
+}
 
-    Ok(())
+fn open_board(app: &mut app::App) {
+    app.board.open();
+    app.previous_mode = app.mode;
+    app.mode = app::Mode::Board;
 }
 
-fn handle_keys_add_workspace(keycode: KeyCode, modifiers: KeyModifiers, mut app: &mut app::App) {
-    match (modifiers, keycode) {
-        (KeyModifiers::NONE, KeyCode::Enter) => {
-            if !app.add_workspace.input_string.is_empty() {
-                app.is_modified = true;
+fn handle_keys_board(key_event: KeyEvent, app: &mut app::App) {
+    let (action, count) = match read_action(app, KeymapMode::Board, key_event) {
+        Some(action) => action,
+        None => return,
+    };
+    let workspace = app.current_workspace;
+    let columns = board_columns(&app.items[workspace], &app.visible_items());
+    let column = app.board.column;
+    let selected = app.board.selected[column];
+    let index = columns[column].get(selected).copied();
 
-                let workspace = app.add_workspace.enter(app.workspaces.len(), 0);
+    if let Some(selected) = list_motion(selected, columns[column].len(), action, count) {
+        app.board.selected[column] = selected;
+        return;
+    }
 
-                app.items.push(Vec::new());
-                app.workspaces.push(workspace);
-                app.current_workspace = app.workspaces.len() - 1;
+    match action {
+        Action::PreviousColumn => app.board.column = column.saturating_sub(1),
+        Action::NextColumn => app.board.column = (column + 1).min(ItemStatus::ALL.len() - 1),
+        Action::MoveToPreviousColumn | Action::MoveToNextColumn => {
+            let target = match action {
+                Action::MoveToPreviousColumn if column > 0 => column - 1,
+                Action::MoveToNextColumn if column + 1 < ItemStatus::ALL.len() => column + 1,
+                _ => return,
+            };
+            let index = match index {
+                Some(index) => index,
+                None => return,
+            };
+            let status = ItemStatus::ALL[target];
 
-                app.add_workspace.reset();
+            app.items[workspace][index].set_status(status);
+            app.set_modified();
 
-                app.mode = app.previous_mode;
-            } else {
-                app.mode = app.previous_mode;
+            // The cursor follows the item into its new column.
+            let columns = board_columns(&app.items[workspace], &app.visible_items());
+            app.board.column = target;
+            app.board.selected[target] = columns[target]
+                .iter()
+                .position(|&i| i == index)
+                .unwrap_or(0);
+
+            if let Some(limit) = wip_limit(status).filter(|&limit| columns[target].len() > limit) {
+                app.set_message(
+                    format!("{} is over its limit of {} items", status.title(), limit),
+                    true,
+                );
             }
         }
-        (KeyModifiers::SHIFT, KeyCode::Char(c)) => {
-            app.add_workspace.add_char(c);
-        }
-        (KeyModifiers::NONE, KeyCode::Char(c)) => {
-            app.add_workspace.add_char(c);
-        }
-        (KeyModifiers::NONE, KeyCode::Backspace) => {
-            app.add_workspace.del_char();
+        Action::EditItem => {
+            if let Some(index) = index {
+                app.select_index(index);
+                app.edit_item.open(&app.items[workspace][index]);
+                app.previous_mode = app::Mode::Board;
+                app.mode = app::Mode::EditItem;
+            }
         }
-        (KeyModifiers::NONE, KeyCode::Esc) => {
-            app.add_workspace.reset();
-            app.mode = app.previous_mode;
+        Action::GoToItem => {
+            if let Some(index) = index {
+                app.select_index(index);
+                app.mode = app::Mode::DisplayItem;
+            }
         }
+        Action::Close => app.mode = app::Mode::DisplayItem,
         _ => {}
     }
 }
 
-fn handle_keys_edit_workspace(keycode: KeyCode, modifiers: KeyModifiers, mut app: &mut app::App) {
-    match (modifiers, keycode) {
-        (KeyModifiers::NONE, KeyCode::Enter) => {
-            app.is_modified = true;
+fn open_dashboard(app: &mut app::App) {
+    app.previous_mode = app.mode;
+    app.mode = app::Mode::Dashboard;
+}
+
+fn handle_keys_dashboard(key_event: KeyEvent, app: &mut app::App) {
+    if let Some((Action::Close, _count)) = read_action(app, KeymapMode::Dashboard, key_event) {
+        close_view(app);
+    }
+}
 
-            let current_title = app.workspaces[app.current_workspace].title.clone();
+// Leaves a view over all workspaces for the items, or the workspaces when
+// there are none.
+fn close_view(app: &mut app::App) {
+    app.mode = if app.workspaces.is_empty() {
+        app::Mode::DisplayWorkspace
+    } else {
+        app::Mode::DisplayItem
+    };
+}
 
-            if app.edit_workspace.input_string.is_empty() {
-                app.edit_workspace.input_string = current_title.clone();
-            }
+// Finds a workspace by its title, or by the start of it when that is unique.
+fn find_workspace(app: &app::App, name: &str) -> Result<usize, Error> {
+    let name = name.to_lowercase();
+    let titles: Vec<String> = app
+        .workspaces
+        .iter()
+        .map(|workspace| workspace.title.to_lowercase())
+        .collect();
 
-            let workspace = app.edit_workspace.enter(
-                app.current_workspace,
-                app.workspaces[app.current_workspace].num_of_item,
-            );
+    if let Some(workspace) = titles.iter().position(|title| *title == name) {
+        return Ok(workspace);
+    }
 
-            app.workspaces[app.current_workspace] = workspace;
+    let matches: Vec<usize> = (0..titles.len())
+        .filter(|&workspace| titles[workspace].starts_with(&name))
+        .collect();
 
-            app.edit_workspace.reset();
-            app.mode = app.previous_mode;
-        }
-        (KeyModifiers::SHIFT, KeyCode::Char(c)) => {
-            app.edit_workspace.add_char(c);
-        }
-        (KeyModifiers::NONE, KeyCode::Char(c)) => {
-            app.edit_workspace.add_char(c);
-        }
-        (KeyModifiers::NONE, KeyCode::Backspace) => {
-            app.edit_workspace.del_char();
-        }
-        (KeyModifiers::NONE, KeyCode::Esc) => {
-            app.edit_workspace.reset();
-            app.mode = app.previous_mode;
-        }
-        _ => {}
+    match matches[..] {
+        [workspace] => Ok(workspace),
+        [] => Err(format_err!("No workspace '{}'", name)),
+        _ => Err(format_err!("More than one workspace matches '{}'", name)),
     }
 }
 
-fn handle_keys_display_workspace(keycode: KeyCode, mut app: &mut app::App) {
-    match keycode {
-        KeyCode::Enter => {
-            if !app.workspaces.is_empty() {
-                app.current_item = 0;
+fn run_command(app: &mut app::App, command: Command) {
+    match command {
+        Command::Add(text) => {
+            if app.workspaces.is_empty() {
+                app.set_message("No workspace to add the item to".to_string(), true);
+            } else if text.is_empty() {
                 app.previous_mode = app.mode;
-                app.mode = app::Mode::DisplayItem;
+                app.mode = app::Mode::AddItem;
+            } else {
+                let index = app.items[app.current_workspace].len();
+                let item = ItemState::new(index, text, String::new(), false, false);
+
+                app.items[app.current_workspace].push(item);
+                app.select_index(index);
+                app.set_modified();
             }
         }
-        KeyCode::Char('j') => {
-            if !app.workspaces.is_empty() {
-                if app.current_workspace == app.workspaces.len() - 1 {
-                    app.current_workspace = app.current_workspace;
-                } else {
-                    app.current_workspace += 1;
+        Command::Move(name) => {
+            let targets = app.targets();
+
+            if targets.is_empty() {
+                app.set_message("No item to move".to_string(), true);
+            } else {
+                match find_workspace(app, &name) {
+                    Ok(target) => move_targets(app, targets, target),
+                    Err(err) => app.set_message(err.to_string(), true),
                 }
-                app.summary_scroll_state.queued_scroll = Some(ScrollDirection::Down);
             }
         }
-        KeyCode::Char('k') => {
+        Command::Sort(sort) => {
             if !app.workspaces.is_empty() {
-                if app.current_workspace == 0 {
-                    app.current_workspace = app.current_workspace;
-                } else {
-                    app.current_workspace -= 1;
+                let selected = app.selected_index();
+
+                app.workspaces[app.current_workspace].sort = sort;
+                app.set_modified();
+
+                match selected {
+                    Some(index) => app.select_index(index),
+                    None => app.current_item = 0,
                 }
-                app.summary_scroll_state.queued_scroll = Some(ScrollDirection::Up);
             }
         }
-        KeyCode::Char('a') => {
-            app.previous_mode = app.mode;
-            app.mode = app::Mode::AddWorkspace;
+        Command::Filter(query) => {
+            app.search.reset();
+            app.search.input.set_text(query);
+            update_search(app);
         }
-        KeyCode::Char('e') => {
-            app.previous_mode = app.mode;
-            app.mode = app::Mode::EditWorkspace;
+        Command::Theme(name) => match theme::get_theme(&CONFIG, &name) {
+            Ok(theme) => theme::set_theme(theme),
+            Err(err) => app.set_message(format!("Could not load theme: {}", err), true),
+        },
+        Command::Archive => archive_finished(app),
+        Command::Export(format, path) => match export::export(app, format, &path) {
+            Ok(num_of_item) => app.set_message(
+                format!(
+                    "Exported {} item{} to {}",
+                    num_of_item,
+                    if num_of_item == 1 { "" } else { "s" },
+                    path
+                ),
+                false,
+            ),
+            Err(err) => app.set_message(format!("Could not export: {}", err), true),
+        },
+        Command::Write => {
+            save(app);
         }
-        KeyCode::Char('d') => {
-            if !app.workspaces.is_empty() {
-                app.items.remove(app.current_workspace);
-                app.workspaces.remove(app.current_workspace);
+        Command::Quit => {
+            if app.is_modified {
+                app.set_message(
+                    "Unsaved changes, use :wq to save or :q! to discard them".to_string(),
+                    true,
+                );
+            } else {
+                quit();
+            }
+        }
+        Command::ForceQuit => quit(),
+        Command::WriteQuit => save_and_quit(app),
+    }
+}
 
-                for workspace in app.workspaces.iter_mut() {
-                    if workspace.slot > app.current_workspace {
-                        workspace.slot -= 1;
-                    }
-                }
+fn handle_keys_command_line(key_event: KeyEvent, app: &mut app::App) {
+    match read_prompt_action(KeymapMode::CommandLine, key_event) {
+        Some(Action::Submit) => {
+            let text = app.command_line.input.text().to_string();
 
-                if app.current_workspace != 0 {
-                    app.current_workspace -= 1;
-                }
+            app.command_line.reset();
+            app.mode = app.previous_mode;
 
-                app.is_modified = true;
+            if !text.trim().is_empty() {
+                match command::parse(&text) {
+                    Ok(command) => run_command(app, command),
+                    Err(err) => app.set_message(err.to_string(), true),
+                }
             }
         }
-        KeyCode::Char('s') => {
-            write_items(app).expect("could not store content");
-            app.is_modified = false;
+        Some(Action::Cancel) => {
+            app.command_line.reset();
+            app.mode = app.previous_mode;
         }
-        KeyCode::Char('?') => {
-            app.previous_mode = app.mode;
-            app.mode = app::Mode::DisplayHelp;
+        Some(action @ Action::Complete) | Some(action @ Action::CompletePrevious) => {
+            let (start, candidates) = command::complete(app, app.command_line.input.text());
+            app.command_line
+                .complete(start, candidates, action == Action::Complete);
+        }
+        _ if key_event.code == KeyCode::Backspace && app.command_line.input.is_empty() => {
+            app.mode = app.previous_mode;
+        }
+        _ => {
+            app.command_line
+                .handle_key(key_event.code, key_event.modifiers);
         }
-        _ => {}
     }
 }
 
-fn handle_keys_add_item(keycode: KeyCode, modifiers: KeyModifiers, mut app: &mut app::App) {
-    match (modifiers, keycode) {
-        (KeyModifiers::NONE, KeyCode::Enter) => {
-            app.add_item.has_expire_datetime = false;
-
-            if !app.add_item.input_string.is_empty() {
-                app.is_modified = true;
+fn handle_keys_palette(key_event: KeyEvent, app: &mut app::App) {
+    match read_prompt_action(KeymapMode::Palette, key_event) {
+        Some(Action::Submit) => {
+            let action = app.palette.selected_action();
 
-                let item = app
-                    .add_item
-                    .enter(app.workspaces[app.current_workspace].num_of_item);
-
-                app.items[app.current_workspace].push(item);
-                app.current_item = app.workspaces[app.current_workspace].num_of_item;
+            app.mode = app.previous_mode;
 
-                app.add_item.reset();
-                app.mode = app.previous_mode;
-            } else {
-                app.mode = app.previous_mode;
+            if let Some(action) = action {
+                match app.palette.mode {
+                    KeymapMode::Item => run_item_action(app, action, None),
+                    KeymapMode::Workspace => run_workspace_action(app, action, None),
+                    _ => {}
+                }
             }
         }
-        (KeyModifiers::CONTROL, KeyCode::Char('d')) => {
-            if app.add_item.has_expire_datetime {
-                app.add_item.has_expire_datetime = false;
-            } else {
-                app.add_item.has_expire_datetime = true;
-            }
+        Some(Action::Cancel) => {
+            app.mode = app.previous_mode;
         }
-        (KeyModifiers::SHIFT, KeyCode::Char(c)) => {
-            app.add_item.add_char(c);
+        Some(Action::ScrollDown) => app.palette.select_next(),
+        Some(Action::ScrollUp) => app.palette.select_previous(),
+        _ => {
+            app.palette.handle_key(key_event.code, key_event.modifiers);
         }
-        (KeyModifiers::NONE, KeyCode::Char(c)) => {
-            app.add_item.add_char(c);
+    }
+}
+
+fn handle_keys_move_item(key_event: KeyEvent, app: &mut app::App) {
+    let (workspace, indices) = match app.move_item.clone() {
+        Some(move_item) => move_item,
+        None => {
+            app.mode = app.previous_mode;
+            return;
         }
-        (KeyModifiers::NONE, KeyCode::Backspace) => {
-            app.add_item.del_char();
+    };
+
+    let (action, count) = match read_action(app, KeymapMode::MoveItem, key_event) {
+        Some(action) => action,
+        None => return,
+    };
+
+    if let Some(target) = list_motion(app.current_workspace, app.workspaces.len(), action, count) {
+        app.current_workspace = target;
+        return;
+    }
+
+    match action {
+        Action::Submit => {
+            let target = app.current_workspace;
+
+            app.current_workspace = workspace;
+            move_targets(app, indices, target);
+            app.move_item = None;
+            app.mode = app.previous_mode;
         }
-        (KeyModifiers::NONE, KeyCode::Esc) => {
-            app.add_item.reset();
+        Action::Cancel => {
+            app.current_workspace = workspace;
+            app.move_item = None;
             app.mode = app.previous_mode;
         }
         _ => {}
     }
 }
 
-fn handle_keys_edit_item(keycode: KeyCode, modifiers: KeyModifiers, mut app: &mut app::App) {
-    match (modifiers, keycode) {
-        (KeyModifiers::NONE, KeyCode::Enter) => {
-            app.is_modified = true;
-            app.edit_item.has_expire_datetime = false;
-
-            if app.edit_item.input_string.is_empty() {
-                app.edit_item.input_string = app.items[app.current_workspace]
-                    .get(app.current_item)
-                    .unwrap()
-                    .text
-                    .clone();
-            }
+fn handle_keys_display_item(key_event: KeyEvent, app: &mut app::App) {
+    if let Some((action, count)) = read_action(app, KeymapMode::Item, key_event) {
+        run_item_action(app, action, count);
+    }
+}
 
-            if app.edit_item.input_datetime.is_empty() {
-                app.edit_item.input_datetime = app.items[app.current_workspace]
-                    .get(app.current_item)
-                    .unwrap()
-                    .expire_datetime_string
-                    .clone();
-            }
+fn run_item_action(app: &mut app::App, action: Action, count: Option<usize>) {
+    let is_searching_all = app.search.is_active() && app.search.all_workspaces;
+    let is_step = action == Action::ScrollDown || action == Action::ScrollUp;
 
-            let item = app.edit_item.enter(app.current_item);
+    if !(is_searching_all && is_step) && move_cursor(app, KeymapMode::Item, action, count) {
+        return;
+    }
 
-            app.items[app.current_workspace][app.current_item] = item;
+    match action {
+        Action::MoveItemDown => {
+            move_selected_item(app, true);
+        }
+        Action::MoveItemUp => {
+            move_selected_item(app, false);
+        }
+        Action::MoveToWorkspace => {
+            let targets = app.targets();
 
-            app.edit_item.reset();
-            app.mode = app.previous_mode;
+            if !targets.is_empty() {
+                app.move_item = Some((app.current_workspace, targets));
+                app.previous_mode = app.mode;
+                app.mode = app::Mode::MoveItem;
+            }
         }
-        (KeyModifiers::CONTROL, KeyCode::Char('d')) => {
-            if app.edit_item.has_expire_datetime {
-                app.edit_item.has_expire_datetime = false;
+        Action::VisualSelect => {
+            if app.visual_anchor.is_some() {
+                for index in app.selection() {
+                    app.items[app.current_workspace][index].is_marked = true;
+                }
+                app.visual_anchor = None;
             } else {
-                app.edit_item.has_expire_datetime = true;
+                app.visual_anchor = app.selected_index();
             }
         }
-        (KeyModifiers::SHIFT, KeyCode::Char(c)) => {
-            app.edit_item.add_char(c);
-        }
-        (KeyModifiers::NONE, KeyCode::Char(c)) => {
-            app.edit_item.add_char(c);
+        Action::ToggleMark => {
+            if let Some(index) = app.selected_index() {
+                let item = &mut app.items[app.current_workspace][index];
+                item.is_marked = !item.is_marked;
+            }
         }
-        (KeyModifiers::NONE, KeyCode::Backspace) => {
-            app.edit_item.del_char();
+        Action::ToggleDetails => app.show_details = !app.show_details,
+        Action::CycleCountdown => {
+            app.countdown = app.countdown.next();
+            app.set_message(format!("Countdown: {}", app.countdown.name()), false);
         }
-        (KeyModifiers::NONE, KeyCode::Esc) => {
-            app.edit_item.reset();
-            app.mode = app.previous_mode;
+        Action::SetDeadline => open_prompt(app, PromptAction::SetDeadline),
+        Action::AddTag => open_prompt(app, PromptAction::AddTag),
+        Action::SetPriority => open_prompt(app, PromptAction::SetPriority),
+        Action::SetReminders => open_prompt(app, PromptAction::SetReminders),
+        Action::Snooze => open_prompt(app, PromptAction::Snooze),
+        Action::SnoozeAndDefer => open_prompt(app, PromptAction::SnoozeAndDefer),
+        Action::ArchiveItem => {
+            let targets = app.targets();
+            archive_targets(app, targets);
         }
-        _ => {}
-    }
-}
+        Action::ArchiveFinished => archive_finished(app),
+        Action::ToggleSnoozed => {
+            let selected = app.selected_index();
 
-fn handle_keys_display_item(keycode: KeyCode, mut app: &mut app::App) {
-    match keycode {
-        KeyCode::Char('J') => {
-            app.current_item = 0;
-            if !app.workspaces.is_empty() {
-                if app.current_workspace == app.workspaces.len() - 1 {
-                    app.current_workspace = app.current_workspace;
+            app.show_snoozed = !app.show_snoozed;
+            app.set_message(
+                if app.show_snoozed {
+                    "Showing snoozed items"
                 } else {
-                    app.current_workspace += 1;
+                    "Hiding snoozed items"
                 }
-                app.summary_scroll_state.queued_scroll = Some(ScrollDirection::Down);
+                .to_string(),
+                false,
+            );
+
+            match selected {
+                Some(index) => app.select_index(index),
+                None => app.current_item = 0,
             }
+            app.current_item = app
+                .current_item
+                .min(app.visible_items().len().saturating_sub(1));
         }
-        KeyCode::Char('K') => {
+        Action::NextWorkspace => {
             app.current_item = 0;
+            app.clear_selection();
             if !app.workspaces.is_empty() {
-                if app.current_workspace == 0 {
-                    app.current_workspace = app.current_workspace;
-                } else {
-                    app.current_workspace -= 1;
-                }
-                app.summary_scroll_state.queued_scroll = Some(ScrollDirection::Up);
+                app.current_workspace = app
+                    .current_workspace
+                    .saturating_add(count.unwrap_or(1))
+                    .min(app.workspaces.len() - 1);
             }
         }
-        KeyCode::Char('j') => {
-            if !app.items.is_empty() && app.workspaces[app.current_workspace].num_of_item != 0 {
-                if app.current_item == app.workspaces[app.current_workspace].num_of_item - 1 {
-                    app.current_item = app.current_item;
-                } else {
-                    app.current_item += 1;
-                }
-                app.summary_scroll_state.queued_scroll = Some(ScrollDirection::Down);
+        Action::PreviousWorkspace => {
+            app.current_item = 0;
+            app.clear_selection();
+            if !app.workspaces.is_empty() {
+                app.current_workspace = app.current_workspace.saturating_sub(count.unwrap_or(1));
             }
         }
-        KeyCode::Char('k') => {
-            if !app.items.is_empty() && app.workspaces[app.current_workspace].num_of_item != 0 {
-                if app.current_item == 0 {
-                    app.current_item = app.current_item;
-                } else {
-                    app.current_item -= 1;
+        Action::ScrollDown => {
+            for _ in 0..count.unwrap_or(1) {
+                step_match(app, true, false);
+            }
+        }
+        Action::ScrollUp => {
+            for _ in 0..count.unwrap_or(1) {
+                step_match(app, false, false);
+            }
+        }
+        Action::NextLate => jump_to_item(app, true, count, is_late, "late"),
+        Action::PreviousLate => jump_to_item(app, false, count, is_late, "late"),
+        Action::NextDue => jump_to_item(app, true, count, is_due, "due"),
+        Action::PreviousDue => jump_to_item(app, false, count, is_due, "due"),
+        Action::Search => {
+            app.previous_mode = app.mode;
+            app.mode = app::Mode::Search;
+        }
+        Action::NextMatch => {
+            if app.search.is_active() {
+                step_match(app, true, true);
+            }
+        }
+        Action::PreviousMatch => {
+            if app.search.is_active() {
+                step_match(app, false, true);
+            }
+        }
+        Action::Clear => {
+            if !app.selection().is_empty() || app.visual_anchor.is_some() {
+                app.clear_selection();
+            } else if app.search.is_active() {
+                let selected = app.selected_index();
+
+                app.search.reset();
+                if let Some(index) = selected {
+                    app.select_index(index);
                 }
-                app.summary_scroll_state.queued_scroll = Some(ScrollDirection::Up);
             }
         }
-        KeyCode::Char('a') => {
+        Action::AddItem => {
             app.previous_mode = app.mode;
             app.mode = app::Mode::AddItem;
         }
-        KeyCode::Char('r') => {
+        Action::EditWorkspace => {
+            app.edit_workspace
+                .open(&app.workspaces[app.current_workspace]);
             app.previous_mode = app.mode;
             app.mode = app::Mode::EditWorkspace;
         }
-        KeyCode::Char('e') => {
-            app.previous_mode = app.mode;
-            app.mode = app::Mode::EditItem;
+        Action::EditItem => {
+            if let Some(index) = app.selected_index() {
+                app.edit_item.open(&app.items[app.current_workspace][index]);
+                app.previous_mode = app.mode;
+                app.mode = app::Mode::EditItem;
+            }
         }
-        KeyCode::Char(' ') => {
-            app.is_modified = true;
+        Action::EditItemInEditor => edit_item_in_editor(app),
+        Action::ToggleFinished => {
+            let targets = app.targets();
+            let selected = app.selected_index();
 
-            let item = app.items[app.current_workspace]
-                .get_mut(app.current_item)
-                .unwrap();
-            if item.is_finished {
-                item.is_finished = false;
-            } else {
-                item.is_finished = true;
+            if !targets.is_empty() {
+                app.set_modified();
+
+                let is_finished = targets
+                    .iter()
+                    .any(|&index| !app.items[app.current_workspace][index].is_finished);
+
+                for index in targets {
+                    app.items[app.current_workspace][index].set_finished(is_finished);
+                }
+
+                app.clear_selection();
+            }
+
+            if let Some(index) = selected {
+                app.select_index(index);
             }
         }
-        KeyCode::Char('x') => {
-            app.is_modified = true;
+        Action::CyclePriority => {
+            if let Some(index) = app.selected_index() {
+                app.set_modified();
 
-            let item = app.items[app.current_workspace]
-                .get_mut(app.current_item)
-                .unwrap();
-            if item.is_late {
-                item.is_late = false;
-            } else {
-                item.is_late = true;
+                let item = &mut app.items[app.current_workspace][index];
+                item.priority = (item.priority + 1) % PRIORITY_NAMES.len();
+
+                app.select_index(index);
             }
         }
-        KeyCode::Char('d') => {
-            let number_of_item = app.items[app.current_workspace].len();
+        Action::CycleSort => {
+            let selected = app.selected_index();
+            let workspace = &mut app.workspaces[app.current_workspace];
+
+            workspace.sort = workspace.sort.next();
+            app.set_modified();
 
-            if number_of_item > 0 {
-                app.items[app.current_workspace].remove(app.current_item);
-                app.is_modified = true;
+            match selected {
+                Some(index) => app.select_index(index),
+                None => app.current_item = 0,
             }
+        }
+        Action::ToggleLate => {
+            if let Some(index) = app.selected_index() {
+                app.set_modified();
 
-            if number_of_item > 0 {
-                for item in app.items[app.current_workspace].iter_mut() {
-                    if item.slot > app.current_item {
-                        item.slot -= 1;
-                    }
+                let item = &mut app.items[app.current_workspace][index];
+                if item.is_late {
+                    item.is_late = false;
+                } else {
+                    item.is_late = true;
                 }
             }
+        }
+        Action::DeleteItem => {
+            let targets = app.targets();
 
-            if app.current_item != 0 {
-                app.current_item -= 1;
+            if targets.len() > 1 {
+                let message = format!(
+                    "Move {} items from '{}' to the trash?",
+                    targets.len(),
+                    app.workspaces[app.current_workspace].title
+                );
+                ask_confirm(app, ConfirmAction::DeleteItems, message);
+            } else {
+                delete_items(app, targets);
             }
         }
-        KeyCode::Char('w') => {
+        Action::AddWorkspace => {
             app.previous_mode = app.mode;
             app.mode = app::Mode::AddWorkspace;
         }
-        KeyCode::Char('-') => {
+        Action::ShowWorkspaces | Action::SwitchPane => {
             app.previous_mode = app.mode;
             app.mode = app::Mode::DisplayWorkspace
         }
-        KeyCode::Char('s') => {
-            write_items(app).expect("could not store content");
-            app.is_modified = false;
+        Action::Save => {
+            save(app);
         }
-        KeyCode::Char('?') => {
+        Action::SaveAndQuit => save_and_quit(app),
+        Action::Quit => quit_without_saving(app),
+        Action::ShowAgenda => open_agenda(app),
+        Action::ShowCalendar => open_calendar(app),
+        Action::ShowDashboard => open_dashboard(app),
+        Action::ShowArchive => open_archive(app),
+        Action::ShowTrash => open_trash(app),
+        Action::ShowBoard => open_board(app),
+        Action::CommandLine => open_command_line(app),
+        Action::Palette => open_palette(app, KeymapMode::Item),
+        Action::Help => {
+            app.help.open(HelpPage::Item);
             app.previous_mode = app.mode;
             app.mode = app::Mode::DisplayHelp;
         }
@@ -415,31 +2108,25 @@ fn handle_keys_display_item(keycode: KeyCode, mut app: &mut app::App) {
     }
 }
 
-fn handle_keys_display_help(keycode: KeyCode, mut app: &mut app::App) {
-    match keycode {
-        KeyCode::Char('j') => {
-            if app.help_mode == app::HelpMode::ItemHelp {
-                app.help_mode = app::HelpMode::WorkspaceHelp;
-            } else {
-                app.help_mode = app::HelpMode::ItemHelp;
+fn handle_keys_display_help(key_event: KeyEvent, app: &mut app::App) {
+    let (action, count) = match read_action(app, KeymapMode::Help, key_event) {
+        Some(action) => action,
+        None => return,
+    };
+
+    match action {
+        Action::ScrollDown => {
+            for _ in 0..count.unwrap_or(1) {
+                app.help.scroll_down();
             }
         }
-        KeyCode::Char('k') => {
-            if app.help_mode == app::HelpMode::WorkspaceHelp {
-                app.help_mode = app::HelpMode::ItemHelp;
-            } else {
-                app.help_mode = app::HelpMode::WorkspaceHelp;
+        Action::ScrollUp => {
+            for _ in 0..count.unwrap_or(1) {
+                app.help.scroll_up();
             }
         }
-        KeyCode::Char('q') => {
-            app.mode = app.previous_mode;
-        }
-        KeyCode::Char('?') => {
-            app.mode = app.previous_mode;
-        }
-        KeyCode::Esc => {
-            app.mode = app.previous_mode;
-        }
+        Action::NextPage => app.help.next_page(),
+        Action::Close => app.mode = app.previous_mode,
         _ => {}
     }
 }
@@ -450,47 +2137,47 @@ pub fn handle_key_bindings(
     app: &mut app::App,
     request_redraw: &Sender<()>,
 ) {
-    match (mode, key_event.modifiers, key_event.code) {
-        (Mode::DisplayHelp, _modifiers, keycode) => {
-            handle_keys_display_help(keycode, app);
-        }
-        (Mode::AddItem, modifiers, keycode) => handle_keys_add_item(keycode, modifiers, app),
-        (Mode::AddWorkspace, modifiers, keycode) => {
-            handle_keys_add_workspace(keycode, modifiers, app)
-        }
-        (Mode::EditItem, modifiers, keycode) => handle_keys_edit_item(keycode, modifiers, app),
-        (Mode::EditWorkspace, modifiers, keycode) => {
-            handle_keys_edit_workspace(keycode, modifiers, app)
-        }
-        (_, KeyModifiers::CONTROL, KeyCode::Char('c')) => {
-            write_items(app).expect("could not store content");
-            cleanup_terminal();
-            std::process::exit(0);
-        }
-        (_, KeyModifiers::NONE, KeyCode::Char('q')) => {
-            write_items(app).expect("could not store content");
-            cleanup_terminal();
-            std::process::exit(0);
-        }
-        (Mode::DisplayItem, _modifiers, keycode) => handle_keys_display_item(keycode, app),
-        (Mode::DisplayWorkspace, _modifiers, keycode) => {
-            handle_keys_display_workspace(keycode, app)
-        }
+    match mode {
+        Mode::DisplayHelp => handle_keys_display_help(key_event, app),
+        Mode::AddItem => handle_keys_add_item(key_event, app),
+        Mode::AddWorkspace => handle_keys_add_workspace(key_event, app),
+        Mode::EditItem => handle_keys_edit_item(key_event, app),
+        Mode::Search => handle_keys_search(key_event, app),
+        Mode::EditWorkspace => handle_keys_edit_workspace(key_event, app),
+        Mode::Confirm => handle_keys_confirm(key_event, app),
+        Mode::MoveItem => handle_keys_move_item(key_event, app),
+        Mode::Prompt => handle_keys_prompt(key_event, app),
+        Mode::CommandLine => handle_keys_command_line(key_event, app),
+        Mode::Palette => handle_keys_palette(key_event, app),
+        Mode::Agenda => handle_keys_agenda(key_event, app),
+        Mode::Archive => handle_keys_archive(key_event, app),
+        Mode::Trash => handle_keys_trash(key_event, app),
+        Mode::Calendar => handle_keys_calendar(key_event, app),
+        Mode::Board => handle_keys_board(key_event, app),
+        Mode::Dashboard => handle_keys_dashboard(key_event, app),
+        Mode::DisplayItem => handle_keys_display_item(key_event, app),
+        Mode::DisplayWorkspace => handle_keys_display_workspace(key_event, app),
     }
 
-    if !app.workspaces.is_empty() {
-        app.workspaces
-            .get_mut(app.current_workspace)
-            .unwrap()
-            .num_of_item = app.items[app.current_workspace].len();
+    finish_event(app, request_redraw);
+}
+
+// Brings the derived state up to date after an event changed the app.
+fn finish_event(app: &mut app::App, request_redraw: &Sender<()>) {
+    for (workspace, items) in app.workspaces.iter_mut().zip(app.items.iter()) {
+        workspace.num_of_item = items.len();
     }
 
     if !app.items.is_empty() {
-        for item in app.items[app.current_workspace].iter_mut() {
-            if item.slot == app.current_item {
-                item.is_selected = true;
-            } else {
-                item.is_selected = false;
+        let selected = app.selected_index();
+
+        for (workspace, items) in app.items.iter_mut().enumerate() {
+            for item in items.iter_mut() {
+                if workspace == app.current_workspace && Some(item.slot) == selected {
+                    item.is_selected = true;
+                } else {
+                    item.is_selected = false;
+                }
             }
         }
     }
@@ -505,5 +2192,148 @@ pub fn handle_key_bindings(
         }
     }
 
+    if app.is_modified && CONFIG.autosave == Autosave::Change {
+        save(app);
+    }
+
     let _ = request_redraw.try_send(());
 }
+
+fn click_item(app: &mut app::App, column: u16, row: u16) -> bool {
+    let item_area = app.item_areas.iter().copied().find(|item_area| {
+        let area = item_area.area;
+        column >= area.x
+            && column < area.x + area.width
+            && row >= area.y
+            && row < area.y + area.height
+    });
+
+    let item_area = match item_area {
+        Some(item_area) => item_area,
+        None => return false,
+    };
+
+    app.current_workspace = item_area.workspace;
+    app.current_item = item_area.row;
+
+    let is_on_mark =
+        row == item_area.area.y && column >= item_area.mark_x && column < item_area.mark_x + 3;
+
+    if is_on_mark {
+        if let Some(index) = app.selected_index() {
+            let item = &mut app.items[app.current_workspace][index];
+            item.set_finished(!item.is_finished);
+
+            app.set_modified();
+            app.select_index(index);
+        }
+    }
+
+    true
+}
+
+fn click_workspace(app: &mut app::App, column: u16, row: u16) -> bool {
+    let workspace = app.workspace_areas.iter().find(|(area, _)| {
+        column >= area.x
+            && column < area.x + area.width
+            && row >= area.y
+            && row < area.y + area.height
+    });
+
+    match workspace {
+        Some(&(_, workspace)) => {
+            app.current_workspace = workspace;
+            true
+        }
+        None => false,
+    }
+}
+
+pub fn handle_mouse(mouse_event: MouseEvent, app: &mut app::App, request_redraw: &Sender<()>) {
+    let (column, row) = (mouse_event.column, mouse_event.row);
+
+    match (app.mode, mouse_event.kind) {
+        (Mode::DisplayItem, MouseEventKind::Down(MouseButton::Left)) => {
+            let workspace = app.current_workspace;
+
+            if click_workspace(app, column, row) {
+                if app.current_workspace != workspace {
+                    app.current_item = 0;
+                }
+            } else {
+                click_item(app, column, row);
+            }
+        }
+        (Mode::DisplayItem, MouseEventKind::ScrollDown) => {
+            run_item_action(app, Action::ScrollDown, None);
+        }
+        (Mode::DisplayItem, MouseEventKind::ScrollUp) => {
+            run_item_action(app, Action::ScrollUp, None);
+        }
+        (Mode::DisplayWorkspace, MouseEventKind::Down(MouseButton::Left)) => {
+            if click_workspace(app, column, row) {
+                run_workspace_action(app, Action::OpenWorkspace, None);
+            } else if click_item(app, column, row) {
+                app.previous_mode = app.mode;
+                app.mode = app::Mode::DisplayItem;
+            }
+        }
+        (Mode::DisplayWorkspace, MouseEventKind::ScrollDown) => {
+            run_workspace_action(app, Action::ScrollDown, None);
+        }
+        (Mode::DisplayWorkspace, MouseEventKind::ScrollUp) => {
+            run_workspace_action(app, Action::ScrollUp, None);
+        }
+        (Mode::MoveItem, MouseEventKind::Down(MouseButton::Left)) => {
+            click_workspace(app, column, row);
+        }
+        (Mode::MoveItem, MouseEventKind::ScrollDown) => {
+            if app.current_workspace + 1 < app.workspaces.len() {
+                app.current_workspace += 1;
+            }
+        }
+        (Mode::MoveItem, MouseEventKind::ScrollUp) => {
+            app.current_workspace = app.current_workspace.saturating_sub(1);
+        }
+        _ => return,
+    }
+
+    finish_event(app, request_redraw);
+}
+
+// Pasted text is inserted into the open prompt with line breaks folded into
+// spaces, so a multi-line paste can't submit it. Elsewhere it is dropped
+// rather than replayed as commands.
+pub fn handle_paste(text: &str, app: &mut app::App, request_redraw: &Sender<()>) {
+    let text = text.replace("\r\n", " ").replace(['\r', '\n', '\t'], " ");
+
+    match app.mode {
+        Mode::AddItem => app.add_item.paste(&text),
+        Mode::EditItem => app.edit_item.paste(&text),
+        Mode::AddWorkspace => app.add_workspace.paste(&text),
+        Mode::EditWorkspace => app.edit_workspace.paste(&text),
+        Mode::Search => {
+            app.search.input.insert_str(&text);
+            update_search(app);
+        }
+        Mode::Prompt => app.prompt.input.insert_str(&text),
+        Mode::CommandLine => app.command_line.paste(&text),
+        Mode::Palette => app.palette.paste(&text),
+        _ => return,
+    }
+
+    finish_event(app, request_redraw);
+}
+
+pub fn handle_tick(app: &mut app::App, request_redraw: &Sender<()>) {
+    if reminder::check(app) {
+        let _ = request_redraw.try_send(());
+    }
+
+    if let (Autosave::Debounce, Some(modified_at)) = (CONFIG.autosave, app.modified_at) {
+        if modified_at.elapsed() >= Duration::from_secs(CONFIG.autosave_delay) {
+            save(app);
+            let _ = request_redraw.try_send(());
+        }
+    }
+}
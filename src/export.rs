@@ -0,0 +1,123 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{format_err, Error};
+
+use crate::app::App;
+use crate::widget::{ItemState, PRIORITY_NAMES};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    Markdown,
+    Csv,
+}
+
+impl ExportFormat {
+    pub const NAMES: [&'static str; 2] = ["md", "csv"];
+
+    pub fn from_name(name: &str) -> Option<ExportFormat> {
+        match name {
+            "md" | "markdown" => Some(ExportFormat::Markdown),
+            "csv" => Some(ExportFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+fn format_markdown_item(item: &ItemState) -> String {
+    let mut line = format!(
+        "- [{}] {}",
+        if item.is_finished { 'x' } else { ' ' },
+        item.text
+    );
+
+    if item.has_expire_datetime {
+        line.push_str(&format!(" (due {})", item.expire_datetime_string));
+    }
+    if item.priority > 0 {
+        line.push_str(&format!(" ({} priority)", PRIORITY_NAMES[item.priority]));
+    }
+    for tag in &item.tags {
+        line.push_str(&format!(" #{}", tag));
+    }
+    line.push('\n');
+
+    // Notes are indented so they stay part of the list item.
+    for note in item.notes.lines() {
+        if !note.is_empty() {
+            line.push_str("  ");
+            line.push_str(note);
+        }
+        line.push('\n');
+    }
+
+    line
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn format_csv_item(workspace: &str, item: &ItemState) -> String {
+    let fields = [
+        csv_field(workspace),
+        csv_field(&item.text),
+        if item.has_expire_datetime {
+            csv_field(&item.expire_datetime_string)
+        } else {
+            String::new()
+        },
+        item.is_finished.to_string(),
+        PRIORITY_NAMES[item.priority].to_string(),
+        csv_field(&item.tags.join(" ")),
+        csv_field(&item.notes),
+    ];
+
+    format!("{}\n", fields.join(","))
+}
+
+// Writes every workspace, in the order each one is sorted by, to `path` and
+// returns the number of items written.
+pub fn export(app: &App, format: ExportFormat, path: &str) -> Result<usize, Error> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => dirs_next::home_dir()
+            .ok_or_else(|| format_err!("no home directory"))?
+            .join(rest),
+        None => PathBuf::from(path),
+    };
+
+    let mut text = match format {
+        ExportFormat::Markdown => String::new(),
+        ExportFormat::Csv => "workspace,text,deadline,finished,priority,tags,notes\n".to_string(),
+    };
+    let mut num_of_item = 0;
+
+    for (workspace, state) in app.workspaces.iter().enumerate() {
+        let items = app.sorted_items_in(workspace);
+
+        if format == ExportFormat::Markdown {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text.push_str(&format!("# {}\n\n", state.title));
+        }
+
+        for index in items {
+            let item = &app.items[workspace][index];
+
+            text.push_str(&match format {
+                ExportFormat::Markdown => format_markdown_item(item),
+                ExportFormat::Csv => format_csv_item(&state.title, item),
+            });
+            num_of_item += 1;
+        }
+    }
+
+    fs::write(&path, text)?;
+
+    Ok(num_of_item)
+}
@@ -0,0 +1,1020 @@
+use std::collections::BTreeMap;
+use std::process;
+
+use anyhow::{format_err, Error};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::KeymapConfig;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+        // Shift is already part of the character, and terminals disagree on
+        // whether to report it for symbols such as `?` or `#`.
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+
+        KeyChord { code, modifiers }
+    }
+
+    pub fn from_event(key_event: KeyEvent) -> KeyChord {
+        KeyChord::new(key_event.code, key_event.modifiers)
+    }
+
+    // Parses chords such as `j`, `J`, `ctrl-k`, `alt-shift-b`, `space` or `esc`.
+    pub fn parse(text: &str) -> Result<KeyChord, Error> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = text;
+
+        loop {
+            let lower = key.to_lowercase();
+
+            if lower.starts_with("ctrl-") && key.len() > 5 {
+                modifiers |= KeyModifiers::CONTROL;
+                key = &key[5..];
+            } else if lower.starts_with("alt-") && key.len() > 4 {
+                modifiers |= KeyModifiers::ALT;
+                key = &key[4..];
+            } else if lower.starts_with("shift-") && key.len() > 6 {
+                modifiers |= KeyModifiers::SHIFT;
+                key = &key[6..];
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format_err!("unknown key `{}`", text)),
+                },
+            },
+        };
+
+        Ok(KeyChord::new(code, modifiers))
+    }
+
+    pub fn name(&self) -> String {
+        let mut name = String::new();
+
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            name.push_str("Ctrl-");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            name.push_str("Alt-");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            name.push_str("Shift-");
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => name.push_str("Space"),
+            KeyCode::Char(c) => name.push(c),
+            KeyCode::F(n) => name.push_str(&format!("F{}", n)),
+            KeyCode::Esc => name.push_str("Escape"),
+            KeyCode::PageUp => name.push_str("PageUp"),
+            KeyCode::PageDown => name.push_str("PageDown"),
+            code => name.push_str(&format!("{:?}", code)),
+        }
+
+        name
+    }
+}
+
+// One or more chords pressed in turn, such as `g g`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct KeySequence(pub Vec<KeyChord>);
+
+impl KeySequence {
+    pub fn parse(text: &str) -> Result<KeySequence, Error> {
+        let chords = text
+            .split_whitespace()
+            .map(KeyChord::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if chords.is_empty() {
+            return Err(format_err!("empty key"));
+        }
+
+        Ok(KeySequence(chords))
+    }
+
+    pub fn name(&self) -> String {
+        self.0.iter().map(KeyChord::name).collect()
+    }
+}
+
+// Result of looking up the keys pressed so far.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum KeyLookup {
+    Action(Action),
+    Pending,
+    Unbound,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum KeymapMode {
+    Item,
+    Workspace,
+    ItemPrompt,
+    Prompt,
+    Search,
+    MoveItem,
+    Agenda,
+    Calendar,
+    CalendarDay,
+    Board,
+    Dashboard,
+    Archive,
+    Trash,
+    Help,
+    CommandLine,
+    Palette,
+    Confirm,
+}
+
+impl KeymapMode {
+    pub const ALL: [KeymapMode; 17] = [
+        KeymapMode::Item,
+        KeymapMode::Workspace,
+        KeymapMode::ItemPrompt,
+        KeymapMode::Prompt,
+        KeymapMode::Search,
+        KeymapMode::MoveItem,
+        KeymapMode::Agenda,
+        KeymapMode::Calendar,
+        KeymapMode::CalendarDay,
+        KeymapMode::Board,
+        KeymapMode::Dashboard,
+        KeymapMode::Archive,
+        KeymapMode::Trash,
+        KeymapMode::Help,
+        KeymapMode::CommandLine,
+        KeymapMode::Palette,
+        KeymapMode::Confirm,
+    ];
+
+    // The `[keymap.<name>]` table of the configuration.
+    pub fn name(self) -> &'static str {
+        match self {
+            KeymapMode::Item => "item",
+            KeymapMode::Workspace => "workspace",
+            KeymapMode::ItemPrompt => "item_prompt",
+            KeymapMode::Prompt => "prompt",
+            KeymapMode::Search => "search",
+            KeymapMode::MoveItem => "move_item",
+            KeymapMode::Agenda => "agenda",
+            KeymapMode::Calendar => "calendar",
+            KeymapMode::CalendarDay => "calendar_day",
+            KeymapMode::Board => "board",
+            KeymapMode::Dashboard => "dashboard",
+            KeymapMode::Archive => "archive",
+            KeymapMode::Trash => "trash",
+            KeymapMode::Help => "help",
+            KeymapMode::CommandLine => "command_line",
+            KeymapMode::Palette => "palette",
+            KeymapMode::Confirm => "confirm",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            KeymapMode::Item => "Item Display:",
+            KeymapMode::Workspace => "Workspace Display:",
+            KeymapMode::ItemPrompt => "Add/Edit Item:",
+            KeymapMode::Prompt => "Prompts:",
+            KeymapMode::Search => "Search:",
+            KeymapMode::MoveItem => "Move Item:",
+            KeymapMode::Agenda => "Agenda:",
+            KeymapMode::Calendar => "Calendar:",
+            KeymapMode::CalendarDay => "Calendar Day List:",
+            KeymapMode::Board => "Board:",
+            KeymapMode::Dashboard => "Dashboard:",
+            KeymapMode::Archive => "Archive:",
+            KeymapMode::Trash => "Trash:",
+            KeymapMode::Help => "Help Window:",
+            KeymapMode::CommandLine => "Command Line:",
+            KeymapMode::Palette => "Command Palette:",
+            KeymapMode::Confirm => "Confirm Dialog:",
+        }
+    }
+
+    fn defaults(self) -> &'static [(&'static str, Action)] {
+        match self {
+            KeymapMode::Item => ITEM_BINDINGS,
+            KeymapMode::Workspace => WORKSPACE_BINDINGS,
+            KeymapMode::ItemPrompt => ITEM_PROMPT_BINDINGS,
+            KeymapMode::Prompt => PROMPT_BINDINGS,
+            KeymapMode::Search => SEARCH_BINDINGS,
+            KeymapMode::MoveItem => MOVE_ITEM_BINDINGS,
+            KeymapMode::Agenda => AGENDA_BINDINGS,
+            KeymapMode::Calendar => CALENDAR_BINDINGS,
+            KeymapMode::CalendarDay => CALENDAR_DAY_BINDINGS,
+            KeymapMode::Board => BOARD_BINDINGS,
+            KeymapMode::Dashboard => DASHBOARD_BINDINGS,
+            KeymapMode::Archive => ARCHIVE_BINDINGS,
+            KeymapMode::Trash => TRASH_BINDINGS,
+            KeymapMode::Help => HELP_BINDINGS,
+            KeymapMode::CommandLine => COMMAND_LINE_BINDINGS,
+            KeymapMode::Palette => PALETTE_BINDINGS,
+            KeymapMode::Confirm => CONFIRM_BINDINGS,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Action {
+    ScrollDown,
+    ScrollUp,
+    HalfPageDown,
+    HalfPageUp,
+    GoToTop,
+    GoToBottom,
+    ScreenTop,
+    ScreenMiddle,
+    ScreenBottom,
+    NextLate,
+    PreviousLate,
+    NextDue,
+    PreviousDue,
+    NextWorkspace,
+    PreviousWorkspace,
+    OpenWorkspace,
+    ShowWorkspaces,
+    SwitchPane,
+    ShowAgenda,
+    ShowCalendar,
+    ShowBoard,
+    ShowDashboard,
+    ShowArchive,
+    ShowTrash,
+    AddWorkspace,
+    EditWorkspace,
+    EditWorkspaceInEditor,
+    DeleteWorkspace,
+    AddItem,
+    EditItem,
+    EditItemInEditor,
+    DeleteItem,
+    ToggleFinished,
+    ToggleLate,
+    CyclePriority,
+    CycleSort,
+    MoveItemDown,
+    MoveItemUp,
+    MoveToWorkspace,
+    VisualSelect,
+    ToggleMark,
+    ToggleDetails,
+    CycleCountdown,
+    SetDeadline,
+    AddTag,
+    SetPriority,
+    SetReminders,
+    Snooze,
+    SnoozeAndDefer,
+    ToggleSnoozed,
+    ArchiveItem,
+    ArchiveFinished,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    Clear,
+    Save,
+    SaveAndQuit,
+    Quit,
+    CommandLine,
+    Palette,
+    Help,
+    Submit,
+    Cancel,
+    ToggleDeadlineInput,
+    ToggleAllWorkspaces,
+    NextPage,
+    Complete,
+    CompletePrevious,
+    GoToItem,
+    Restore,
+    PreviousDay,
+    NextDay,
+    PreviousWeek,
+    NextWeek,
+    PreviousMonth,
+    NextMonth,
+    GoToToday,
+    ListDay,
+    PreviousColumn,
+    NextColumn,
+    MoveToPreviousColumn,
+    MoveToNextColumn,
+    Close,
+}
+
+const ITEM_BINDINGS: &[(&str, Action)] = &[
+    ("k", Action::ScrollUp),
+    ("j", Action::ScrollDown),
+    ("ctrl-u", Action::HalfPageUp),
+    ("ctrl-d", Action::HalfPageDown),
+    ("g g", Action::GoToTop),
+    ("G", Action::GoToBottom),
+    ("H", Action::ScreenTop),
+    ("M", Action::ScreenMiddle),
+    ("L", Action::ScreenBottom),
+    ("] l", Action::NextLate),
+    ("[ l", Action::PreviousLate),
+    ("] d", Action::NextDue),
+    ("[ d", Action::PreviousDue),
+    ("K", Action::PreviousWorkspace),
+    ("J", Action::NextWorkspace),
+    ("w", Action::AddWorkspace),
+    ("-", Action::ShowWorkspaces),
+    ("tab", Action::SwitchPane),
+    ("h", Action::SwitchPane),
+    ("A", Action::ShowAgenda),
+    ("C", Action::ShowCalendar),
+    ("B", Action::ShowBoard),
+    ("O", Action::ShowDashboard),
+    ("g a", Action::ShowArchive),
+    ("g t", Action::ShowTrash),
+    ("r", Action::EditWorkspace),
+    ("a", Action::AddItem),
+    ("e", Action::EditItem),
+    ("E", Action::EditItemInEditor),
+    ("d", Action::DeleteItem),
+    ("space", Action::ToggleFinished),
+    ("x", Action::ToggleLate),
+    ("p", Action::CyclePriority),
+    ("o", Action::CycleSort),
+    ("ctrl-j", Action::MoveItemDown),
+    ("ctrl-k", Action::MoveItemUp),
+    ("m", Action::MoveToWorkspace),
+    ("v", Action::VisualSelect),
+    ("t", Action::ToggleMark),
+    ("i", Action::ToggleDetails),
+    ("c", Action::CycleCountdown),
+    ("D", Action::SetDeadline),
+    ("#", Action::AddTag),
+    ("P", Action::SetPriority),
+    ("R", Action::SetReminders),
+    ("z", Action::Snooze),
+    ("Z", Action::SnoozeAndDefer),
+    ("S", Action::ToggleSnoozed),
+    ("X", Action::ArchiveItem),
+    ("g X", Action::ArchiveFinished),
+    ("/", Action::Search),
+    ("n", Action::NextMatch),
+    ("N", Action::PreviousMatch),
+    ("esc", Action::Clear),
+    ("s", Action::Save),
+    ("q", Action::SaveAndQuit),
+    ("ctrl-c", Action::SaveAndQuit),
+    ("Q", Action::Quit),
+    (":", Action::CommandLine),
+    ("ctrl-p", Action::Palette),
+    ("?", Action::Help),
+];
+
+const WORKSPACE_BINDINGS: &[(&str, Action)] = &[
+    ("k", Action::ScrollUp),
+    ("j", Action::ScrollDown),
+    ("ctrl-u", Action::HalfPageUp),
+    ("ctrl-d", Action::HalfPageDown),
+    ("g g", Action::GoToTop),
+    ("G", Action::GoToBottom),
+    ("H", Action::ScreenTop),
+    ("M", Action::ScreenMiddle),
+    ("L", Action::ScreenBottom),
+    ("enter", Action::OpenWorkspace),
+    ("tab", Action::SwitchPane),
+    ("l", Action::SwitchPane),
+    ("A", Action::ShowAgenda),
+    ("C", Action::ShowCalendar),
+    ("O", Action::ShowDashboard),
+    ("g a", Action::ShowArchive),
+    ("g t", Action::ShowTrash),
+    ("a", Action::AddWorkspace),
+    ("e", Action::EditWorkspace),
+    ("E", Action::EditWorkspaceInEditor),
+    ("d", Action::DeleteWorkspace),
+    ("s", Action::Save),
+    ("q", Action::SaveAndQuit),
+    ("ctrl-c", Action::SaveAndQuit),
+    ("Q", Action::Quit),
+    (":", Action::CommandLine),
+    ("ctrl-p", Action::Palette),
+    ("?", Action::Help),
+];
+
+// Only single chords are looked up while typing, and keys left unbound go to
+// the input.
+const ITEM_PROMPT_BINDINGS: &[(&str, Action)] = &[
+    ("enter", Action::Submit),
+    ("esc", Action::Cancel),
+    ("ctrl-d", Action::ToggleDeadlineInput),
+];
+
+const PROMPT_BINDINGS: &[(&str, Action)] = &[("enter", Action::Submit), ("esc", Action::Cancel)];
+
+const SEARCH_BINDINGS: &[(&str, Action)] = &[
+    ("enter", Action::Submit),
+    ("esc", Action::Cancel),
+    ("tab", Action::ToggleAllWorkspaces),
+];
+
+const MOVE_ITEM_BINDINGS: &[(&str, Action)] = &[
+    ("j", Action::ScrollDown),
+    ("down", Action::ScrollDown),
+    ("k", Action::ScrollUp),
+    ("up", Action::ScrollUp),
+    ("enter", Action::Submit),
+    ("q", Action::Cancel),
+    ("esc", Action::Cancel),
+];
+
+const AGENDA_BINDINGS: &[(&str, Action)] = &[
+    ("j", Action::ScrollDown),
+    ("down", Action::ScrollDown),
+    ("k", Action::ScrollUp),
+    ("up", Action::ScrollUp),
+    ("g", Action::GoToTop),
+    ("home", Action::GoToTop),
+    ("G", Action::GoToBottom),
+    ("end", Action::GoToBottom),
+    ("space", Action::ToggleFinished),
+    ("e", Action::EditItem),
+    ("enter", Action::GoToItem),
+    ("q", Action::Close),
+    ("esc", Action::Close),
+];
+
+const CALENDAR_BINDINGS: &[(&str, Action)] = &[
+    ("h", Action::PreviousDay),
+    ("left", Action::PreviousDay),
+    ("l", Action::NextDay),
+    ("right", Action::NextDay),
+    ("k", Action::PreviousWeek),
+    ("up", Action::PreviousWeek),
+    ("j", Action::NextWeek),
+    ("down", Action::NextWeek),
+    ("[", Action::PreviousMonth),
+    ("pageup", Action::PreviousMonth),
+    ("]", Action::NextMonth),
+    ("pagedown", Action::NextMonth),
+    ("t", Action::GoToToday),
+    ("enter", Action::ListDay),
+    ("q", Action::Close),
+    ("esc", Action::Close),
+];
+
+const CALENDAR_DAY_BINDINGS: &[(&str, Action)] = &[
+    ("j", Action::ScrollDown),
+    ("down", Action::ScrollDown),
+    ("k", Action::ScrollUp),
+    ("up", Action::ScrollUp),
+    ("space", Action::ToggleFinished),
+    ("e", Action::EditItem),
+    ("enter", Action::GoToItem),
+    ("q", Action::Close),
+    ("esc", Action::Close),
+];
+
+const BOARD_BINDINGS: &[(&str, Action)] = &[
+    ("h", Action::PreviousColumn),
+    ("left", Action::PreviousColumn),
+    ("l", Action::NextColumn),
+    ("right", Action::NextColumn),
+    ("j", Action::ScrollDown),
+    ("down", Action::ScrollDown),
+    ("k", Action::ScrollUp),
+    ("up", Action::ScrollUp),
+    ("H", Action::MoveToPreviousColumn),
+    ("L", Action::MoveToNextColumn),
+    ("e", Action::EditItem),
+    ("enter", Action::GoToItem),
+    ("q", Action::Close),
+    ("esc", Action::Close),
+];
+
+const DASHBOARD_BINDINGS: &[(&str, Action)] = &[("q", Action::Close), ("esc", Action::Close)];
+
+const ARCHIVE_BINDINGS: &[(&str, Action)] = &[
+    ("j", Action::ScrollDown),
+    ("down", Action::ScrollDown),
+    ("k", Action::ScrollUp),
+    ("up", Action::ScrollUp),
+    ("g", Action::GoToTop),
+    ("G", Action::GoToBottom),
+    ("/", Action::Search),
+    ("r", Action::Restore),
+    ("enter", Action::Restore),
+    ("q", Action::Close),
+    ("esc", Action::Close),
+];
+
+const TRASH_BINDINGS: &[(&str, Action)] = &[
+    ("j", Action::ScrollDown),
+    ("down", Action::ScrollDown),
+    ("k", Action::ScrollUp),
+    ("up", Action::ScrollUp),
+    ("g", Action::GoToTop),
+    ("G", Action::GoToBottom),
+    ("r", Action::Restore),
+    ("enter", Action::Restore),
+    ("q", Action::Close),
+    ("esc", Action::Close),
+];
+
+const HELP_BINDINGS: &[(&str, Action)] = &[
+    ("j", Action::ScrollDown),
+    ("down", Action::ScrollDown),
+    ("k", Action::ScrollUp),
+    ("up", Action::ScrollUp),
+    ("tab", Action::NextPage),
+    ("backtab", Action::NextPage),
+    ("h", Action::NextPage),
+    ("l", Action::NextPage),
+    ("q", Action::Close),
+    ("?", Action::Close),
+    ("esc", Action::Close),
+];
+
+const COMMAND_LINE_BINDINGS: &[(&str, Action)] = &[
+    ("enter", Action::Submit),
+    ("esc", Action::Cancel),
+    ("tab", Action::Complete),
+    ("backtab", Action::CompletePrevious),
+];
+
+const PALETTE_BINDINGS: &[(&str, Action)] = &[
+    ("enter", Action::Submit),
+    ("esc", Action::Cancel),
+    ("up", Action::ScrollUp),
+    ("down", Action::ScrollDown),
+    ("backtab", Action::ScrollUp),
+    ("tab", Action::ScrollDown),
+    ("ctrl-p", Action::ScrollUp),
+    ("ctrl-n", Action::ScrollDown),
+];
+
+// Keys left unbound cancel as well.
+const CONFIRM_BINDINGS: &[(&str, Action)] = &[
+    ("y", Action::Submit),
+    ("Y", Action::Submit),
+    ("n", Action::Cancel),
+    ("esc", Action::Cancel),
+];
+
+impl Action {
+    pub const ALL: [Action; 84] = [
+        Action::ScrollDown,
+        Action::ScrollUp,
+        Action::HalfPageDown,
+        Action::HalfPageUp,
+        Action::GoToTop,
+        Action::GoToBottom,
+        Action::ScreenTop,
+        Action::ScreenMiddle,
+        Action::ScreenBottom,
+        Action::NextLate,
+        Action::PreviousLate,
+        Action::NextDue,
+        Action::PreviousDue,
+        Action::NextWorkspace,
+        Action::PreviousWorkspace,
+        Action::OpenWorkspace,
+        Action::ShowWorkspaces,
+        Action::SwitchPane,
+        Action::ShowAgenda,
+        Action::ShowCalendar,
+        Action::ShowBoard,
+        Action::ShowDashboard,
+        Action::ShowArchive,
+        Action::ShowTrash,
+        Action::AddWorkspace,
+        Action::EditWorkspace,
+        Action::EditWorkspaceInEditor,
+        Action::DeleteWorkspace,
+        Action::AddItem,
+        Action::EditItem,
+        Action::EditItemInEditor,
+        Action::DeleteItem,
+        Action::ToggleFinished,
+        Action::ToggleLate,
+        Action::CyclePriority,
+        Action::CycleSort,
+        Action::MoveItemDown,
+        Action::MoveItemUp,
+        Action::MoveToWorkspace,
+        Action::VisualSelect,
+        Action::ToggleMark,
+        Action::ToggleDetails,
+        Action::CycleCountdown,
+        Action::SetDeadline,
+        Action::AddTag,
+        Action::SetPriority,
+        Action::SetReminders,
+        Action::Snooze,
+        Action::SnoozeAndDefer,
+        Action::ToggleSnoozed,
+        Action::ArchiveItem,
+        Action::ArchiveFinished,
+        Action::Search,
+        Action::NextMatch,
+        Action::PreviousMatch,
+        Action::Clear,
+        Action::Save,
+        Action::SaveAndQuit,
+        Action::Quit,
+        Action::CommandLine,
+        Action::Palette,
+        Action::Help,
+        Action::Submit,
+        Action::Cancel,
+        Action::ToggleDeadlineInput,
+        Action::ToggleAllWorkspaces,
+        Action::NextPage,
+        Action::Complete,
+        Action::CompletePrevious,
+        Action::GoToItem,
+        Action::Restore,
+        Action::PreviousDay,
+        Action::NextDay,
+        Action::PreviousWeek,
+        Action::NextWeek,
+        Action::PreviousMonth,
+        Action::NextMonth,
+        Action::GoToToday,
+        Action::ListDay,
+        Action::PreviousColumn,
+        Action::NextColumn,
+        Action::MoveToPreviousColumn,
+        Action::MoveToNextColumn,
+        Action::Close,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::ScrollDown => "scroll_down",
+            Action::ScrollUp => "scroll_up",
+            Action::HalfPageDown => "half_page_down",
+            Action::HalfPageUp => "half_page_up",
+            Action::GoToTop => "go_to_top",
+            Action::GoToBottom => "go_to_bottom",
+            Action::ScreenTop => "screen_top",
+            Action::ScreenMiddle => "screen_middle",
+            Action::ScreenBottom => "screen_bottom",
+            Action::NextLate => "next_late",
+            Action::PreviousLate => "previous_late",
+            Action::NextDue => "next_due",
+            Action::PreviousDue => "previous_due",
+            Action::NextWorkspace => "next_workspace",
+            Action::PreviousWorkspace => "previous_workspace",
+            Action::OpenWorkspace => "open_workspace",
+            Action::ShowWorkspaces => "show_workspaces",
+            Action::SwitchPane => "switch_pane",
+            Action::ShowAgenda => "show_agenda",
+            Action::ShowCalendar => "show_calendar",
+            Action::ShowBoard => "show_board",
+            Action::ShowDashboard => "show_dashboard",
+            Action::ShowArchive => "show_archive",
+            Action::ShowTrash => "show_trash",
+            Action::AddWorkspace => "add_workspace",
+            Action::EditWorkspace => "edit_workspace",
+            Action::EditWorkspaceInEditor => "edit_workspace_in_editor",
+            Action::DeleteWorkspace => "delete_workspace",
+            Action::AddItem => "add_item",
+            Action::EditItem => "edit_item",
+            Action::EditItemInEditor => "edit_item_in_editor",
+            Action::DeleteItem => "delete_item",
+            Action::ToggleFinished => "toggle_finished",
+            Action::ToggleLate => "toggle_late",
+            Action::CyclePriority => "cycle_priority",
+            Action::CycleSort => "cycle_sort",
+            Action::MoveItemDown => "move_item_down",
+            Action::MoveItemUp => "move_item_up",
+            Action::MoveToWorkspace => "move_to_workspace",
+            Action::VisualSelect => "visual_select",
+            Action::ToggleMark => "toggle_mark",
+            Action::ToggleDetails => "toggle_details",
+            Action::CycleCountdown => "cycle_countdown",
+            Action::SetDeadline => "set_deadline",
+            Action::AddTag => "add_tag",
+            Action::SetPriority => "set_priority",
+            Action::SetReminders => "set_reminders",
+            Action::Snooze => "snooze",
+            Action::SnoozeAndDefer => "snooze_and_defer",
+            Action::ToggleSnoozed => "toggle_snoozed",
+            Action::ArchiveItem => "archive_item",
+            Action::ArchiveFinished => "archive_finished",
+            Action::Search => "search",
+            Action::NextMatch => "next_match",
+            Action::PreviousMatch => "previous_match",
+            Action::Clear => "clear",
+            Action::Save => "save",
+            Action::SaveAndQuit => "save_and_quit",
+            Action::Quit => "quit",
+            Action::CommandLine => "command_line",
+            Action::Palette => "palette",
+            Action::Help => "help",
+            Action::Submit => "submit",
+            Action::Cancel => "cancel",
+            Action::ToggleDeadlineInput => "toggle_deadline_input",
+            Action::ToggleAllWorkspaces => "toggle_all_workspaces",
+            Action::NextPage => "next_page",
+            Action::Complete => "complete",
+            Action::CompletePrevious => "complete_previous",
+            Action::GoToItem => "go_to_item",
+            Action::Restore => "restore",
+            Action::PreviousDay => "previous_day",
+            Action::NextDay => "next_day",
+            Action::PreviousWeek => "previous_week",
+            Action::NextWeek => "next_week",
+            Action::PreviousMonth => "previous_month",
+            Action::NextMonth => "next_month",
+            Action::GoToToday => "go_to_today",
+            Action::ListDay => "list_day",
+            Action::PreviousColumn => "previous_column",
+            Action::NextColumn => "next_column",
+            Action::MoveToPreviousColumn => "move_to_previous_column",
+            Action::MoveToNextColumn => "move_to_next_column",
+            Action::Close => "close",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::ScrollDown => "Scroll down",
+            Action::ScrollUp => "Scroll up",
+            Action::HalfPageDown => "Scroll down half a page",
+            Action::HalfPageUp => "Scroll up half a page",
+            Action::GoToTop => "Go to first (or count-th) row",
+            Action::GoToBottom => "Go to last (or count-th) row",
+            Action::ScreenTop => "Go to top of screen",
+            Action::ScreenMiddle => "Go to middle of screen",
+            Action::ScreenBottom => "Go to bottom of screen",
+            Action::NextLate => "Go to next late item",
+            Action::PreviousLate => "Go to previous late item",
+            Action::NextDue => "Go to next item with a deadline",
+            Action::PreviousDue => "Go to previous item with a deadline",
+            Action::NextWorkspace => "Scroll workspace down",
+            Action::PreviousWorkspace => "Scroll workspace up",
+            Action::OpenWorkspace => "Enter selected workspace",
+            Action::ShowWorkspaces => "Open workspace window",
+            Action::SwitchPane => "Move focus between workspaces and items",
+            Action::ShowAgenda => "Open agenda of all workspaces",
+            Action::ShowCalendar => "Open month calendar of deadlines",
+            Action::ShowBoard => "Open workspace as a board by status",
+            Action::ShowDashboard => "Open dashboard of statistics",
+            Action::ShowArchive => "Browse and restore archived items",
+            Action::ShowTrash => "Browse and restore deleted items and workspaces",
+            Action::AddWorkspace => "Create new workspace",
+            Action::EditWorkspace => "Rename workspace",
+            Action::EditWorkspaceInEditor => "Edit items of workspace in $EDITOR",
+            Action::DeleteWorkspace => "Move selected workspace to the trash",
+            Action::AddItem => "Open add item window",
+            Action::EditItem => "Open edit item window",
+            Action::EditItemInEditor => "Edit item in $EDITOR",
+            Action::DeleteItem => "Move selected items to the trash",
+            Action::ToggleFinished => "Toggle completion of selected items",
+            Action::ToggleLate => "Toggle item's late status",
+            Action::CyclePriority => "Cycle item's priority",
+            Action::CycleSort => "Cycle sort order",
+            Action::MoveItemDown => "Move item down",
+            Action::MoveItemUp => "Move item up",
+            Action::MoveToWorkspace => "Move selected items to another workspace",
+            Action::VisualSelect => "Start or end a range selection",
+            Action::ToggleMark => "Toggle mark on item",
+            Action::ToggleDetails => "Toggle item detail pane",
+            Action::CycleCountdown => "Cycle countdown format",
+            Action::SetDeadline => "Set deadline of selected items",
+            Action::AddTag => "Add tag to selected items",
+            Action::SetPriority => "Set priority of selected items",
+            Action::SetReminders => "Set reminders of selected items",
+            Action::Snooze => "Snooze selected items",
+            Action::SnoozeAndDefer => "Snooze selected items and push back their deadline",
+            Action::ToggleSnoozed => "Show or hide snoozed items",
+            Action::ArchiveItem => "Archive selected items",
+            Action::ArchiveFinished => "Archive finished items of workspace",
+            Action::Search => "Search items",
+            Action::NextMatch => "Next search match",
+            Action::PreviousMatch => "Previous search match",
+            Action::Clear => "Clear selection, then search",
+            Action::Save => "Save",
+            Action::SaveAndQuit => "Save and quit program",
+            Action::Quit => "Quit program without saving",
+            Action::CommandLine => "Enter a command",
+            Action::Palette => "Search and run any action",
+            Action::Help => "Toggle help window",
+            Action::Submit => "Accept input or selection",
+            Action::Cancel => "Cancel and go back",
+            Action::ToggleDeadlineInput => "Toggle input timestamp window",
+            Action::ToggleAllWorkspaces => "Toggle searching all workspaces",
+            Action::NextPage => "Switch help page",
+            Action::Complete => "Complete command or argument",
+            Action::CompletePrevious => "Go back to previous completion",
+            Action::GoToItem => "Go to item in its workspace",
+            Action::Restore => "Restore selected entry",
+            Action::PreviousDay => "Go to previous day",
+            Action::NextDay => "Go to next day",
+            Action::PreviousWeek => "Go to previous week",
+            Action::NextWeek => "Go to next week",
+            Action::PreviousMonth => "Go to previous month",
+            Action::NextMonth => "Go to next month",
+            Action::GoToToday => "Go to today",
+            Action::ListDay => "List items due on the day",
+            Action::PreviousColumn => "Go to previous column",
+            Action::NextColumn => "Go to next column",
+            Action::MoveToPreviousColumn => "Move item to previous column",
+            Action::MoveToNextColumn => "Move item to next column",
+            Action::Close => "Close view",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+    }
+}
+
+pub struct Keymap {
+    modes: Vec<(KeymapMode, Vec<(KeySequence, Action)>)>,
+}
+
+impl Keymap {
+    fn bindings(&self, mode: KeymapMode) -> &Vec<(KeySequence, Action)> {
+        &self
+            .modes
+            .iter()
+            .find(|(bound_mode, _)| *bound_mode == mode)
+            .unwrap()
+            .1
+    }
+
+    // `keys` are the chords pressed so far, the last one included. A prefix
+    // of a longer binding waits for the next chord.
+    pub fn lookup(&self, mode: KeymapMode, keys: &[KeyChord]) -> KeyLookup {
+        let bindings = self.bindings(mode);
+
+        if let Some((_, action)) = bindings.iter().find(|(bound, _)| bound.0 == keys) {
+            KeyLookup::Action(*action)
+        } else if bindings
+            .iter()
+            .any(|(bound, _)| bound.0.len() > keys.len() && bound.0.starts_with(keys))
+        {
+            KeyLookup::Pending
+        } else {
+            KeyLookup::Unbound
+        }
+    }
+
+    pub fn keys(&self, mode: KeymapMode, action: Action) -> Vec<KeySequence> {
+        self.bindings(mode)
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|(keys, _)| keys.clone())
+            .collect()
+    }
+
+    // A window title followed by hints such as `<r>: restore`, using the first
+    // key bound to each action. Hints without a key are left out.
+    pub fn title(&self, title: &str, mode: KeymapMode, hints: &[(&[Action], &str)]) -> String {
+        let hints: Vec<String> = hints
+            .iter()
+            .filter_map(|&(actions, hint)| {
+                let keys: Vec<String> = actions
+                    .iter()
+                    .filter_map(|&action| self.keys(mode, action).first().map(KeySequence::name))
+                    .map(|key| format!("<{}>", key))
+                    .collect();
+
+                if keys.is_empty() {
+                    None
+                } else {
+                    Some(format!("{}: {}", keys.join("/"), hint))
+                }
+            })
+            .collect();
+
+        if hints.is_empty() {
+            format!(" {} ", title)
+        } else {
+            format!(" {} ({}) ", title, hints.join(", "))
+        }
+    }
+
+    // Actions of `mode` in help order, each with its bound keys.
+    pub fn help(&self, mode: KeymapMode) -> Vec<(Action, Vec<KeySequence>)> {
+        self.actions(mode)
+            .into_iter()
+            .filter(|(_, keys)| !keys.is_empty())
+            .collect()
+    }
+
+    // Every action of `mode`, including the ones left without a key.
+    pub fn actions(&self, mode: KeymapMode) -> Vec<(Action, Vec<KeySequence>)> {
+        let mut actions: Vec<Action> = Vec::new();
+        for &(_, action) in mode.defaults() {
+            if !actions.contains(&action) {
+                actions.push(action);
+            }
+        }
+
+        actions
+            .into_iter()
+            .map(|action| (action, self.keys(mode, action)))
+            .collect()
+    }
+}
+
+fn build_bindings(
+    defaults: &[(&str, Action)],
+    overrides: &BTreeMap<String, String>,
+    section: &str,
+) -> Result<Vec<(KeySequence, Action)>, Error> {
+    let mut bindings = Vec::new();
+
+    for &(key, action) in defaults {
+        bindings.push((KeySequence::parse(key)?, action));
+    }
+
+    for (key, name) in overrides {
+        let keys = KeySequence::parse(key).map_err(|err| format_err!("[{}] {}", section, err))?;
+
+        bindings.retain(|(bound, _)| *bound != keys);
+
+        if name == "none" {
+            continue;
+        }
+
+        match Action::from_name(name) {
+            Some(action) if defaults.iter().any(|&(_, default)| default == action) => {
+                bindings.push((keys, action));
+            }
+            _ => {
+                return Err(format_err!(
+                    "[{}] unknown action `{}` for `{}`",
+                    section,
+                    name,
+                    key
+                ))
+            }
+        }
+    }
+
+    Ok(bindings)
+}
+
+fn get_keymap(config: &KeymapConfig) -> Result<Keymap, Error> {
+    let modes = KeymapMode::ALL
+        .iter()
+        .map(|&mode| {
+            let section = format!("keymap.{}", mode.name());
+            let bindings = build_bindings(mode.defaults(), config.overrides(mode), &section)?;
+
+            Ok((mode, bindings))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(Keymap { modes })
+}
+
+pub fn resolve_keymap(config: &KeymapConfig) -> Keymap {
+    match get_keymap(config) {
+        Ok(keymap) => keymap,
+        Err(err) => {
+            eprintln!("tdr: config.toml: {}", err);
+            process::exit(1);
+        }
+    }
+}
@@ -1,45 +1,43 @@
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::Duration;
 use std::{io, panic, thread};
 
 use lazy_static::lazy_static;
 
 use tui::backend::CrosstermBackend;
-use tui::style::Color;
 use tui::Terminal;
 
-use crossbeam_channel::{bounded, select, unbounded, Receiver, Sender};
-use crossterm::event::Event;
+use crossbeam_channel::{bounded, select, tick, unbounded, Receiver, Sender};
+use crossterm::event::{
+    DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event,
+    KeyEventKind,
+};
 use crossterm::{cursor, execute, terminal};
 
 mod app;
+mod command;
+mod config;
 mod draw;
+mod editor;
 mod event;
+mod export;
+mod keymap;
 mod query;
+mod reminder;
 mod theme;
 mod widget;
 
 lazy_static! {
+    pub static ref CONFIG: config::Config = config::resolve_config();
+    pub static ref KEYMAP: keymap::Keymap = keymap::resolve_keymap(&CONFIG.keymap);
     pub static ref QUERIES: query::Queries = query::resolve_queries();
     pub static ref REDRAW_REQUEST: (Sender<()>, Receiver<()>) = bounded(1);
-    pub static ref THEME: theme::Theme = theme::Theme {
-        background: Color::Reset,
-        unfinished: Color::Red,
-        finished: Color::Green,
-        loss: Color::Red,
-        text_normal: Color::Reset,
-        text_primary: Color::Yellow,
-        text_secondary: Color::Cyan,
-        text_dark: Color::Black,
-        border_primary: Color::Blue,
-        border_secondary: Color::Reset,
-        border_axis: Color::Blue,
-        focused: Color::LightBlue,
-        unfocused: Color::DarkGray,
-    };
+    pub static ref THEME: RwLock<theme::Theme> =
+        RwLock::new(theme::resolve_theme(&CONFIG, QUERIES.theme.as_deref()));
 }
 
-fn setup_terminal() {
+pub fn setup_terminal() {
     let mut stdout = io::stdout();
 
     execute!(stdout, cursor::Hide).unwrap();
@@ -47,15 +45,24 @@ fn setup_terminal() {
 
     execute!(stdout, terminal::Clear(terminal::ClearType::All)).unwrap();
 
+    execute!(stdout, EnableBracketedPaste).unwrap();
+    if CONFIG.mouse {
+        execute!(stdout, EnableMouseCapture).unwrap();
+    }
+
     terminal::enable_raw_mode().unwrap();
 }
 
-fn cleanup_terminal() {
+pub fn cleanup_terminal() {
     let mut stdout = io::stdout();
 
     execute!(stdout, cursor::MoveTo(0, 0)).unwrap();
     execute!(stdout, terminal::Clear(terminal::ClearType::All)).unwrap();
 
+    execute!(stdout, DisableBracketedPaste).unwrap();
+    if CONFIG.mouse {
+        execute!(stdout, DisableMouseCapture).unwrap();
+    }
     execute!(stdout, terminal::LeaveAlternateScreen).unwrap();
     execute!(stdout, cursor::Show).unwrap();
 
@@ -69,29 +76,48 @@ fn setup_panic_hook() {
     }));
 }
 
+static UI_EVENTS_PAUSED: AtomicBool = AtomicBool::new(false);
+static UI_EVENTS_IDLE: AtomicBool = AtomicBool::new(false);
+
+// Stops the input thread from reading the terminal so that another program
+// can use it, and waits until it has.
+pub fn pause_ui_events() {
+    UI_EVENTS_PAUSED.store(true, Ordering::SeqCst);
+
+    while !UI_EVENTS_IDLE.load(Ordering::SeqCst) {
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+pub fn resume_ui_events() {
+    UI_EVENTS_IDLE.store(false, Ordering::SeqCst);
+    UI_EVENTS_PAUSED.store(false, Ordering::SeqCst);
+}
+
 fn setup_ui_events() -> Receiver<Event> {
     let (sender, receiver) = unbounded();
     thread::spawn(move || loop {
+        if UI_EVENTS_PAUSED.load(Ordering::SeqCst) {
+            UI_EVENTS_IDLE.store(true, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(10));
+            continue;
+        }
+
+        if !crossterm::event::poll(Duration::from_millis(50)).unwrap() {
+            continue;
+        }
+
         sender.send(crossterm::event::read().unwrap()).unwrap();
     });
 
     receiver
 }
 
-fn main() {
-    better_panic::install();
-
-    let queries = QUERIES.clone();
-
-    let backend = CrosstermBackend::new(io::stdout());
-    let mut terminal = Terminal::new(backend).unwrap();
-
-    setup_panic_hook();
-    setup_terminal();
-
-    let request_redraw = REDRAW_REQUEST.0.clone();
-    let ui_events = setup_ui_events();
-
+// Builds the workspaces and items stored in ~/.todo.yml, or a single empty
+// workspace.
+pub fn load_items(
+    queries: &query::Queries,
+) -> (Vec<widget::WorkspaceState>, Vec<Vec<widget::ItemState>>) {
     let mut starting_workspaces: Vec<widget::WorkspaceState> = Vec::new();
     let mut starting_items: Vec<Vec<widget::ItemState>> = Vec::new();
 
@@ -140,6 +166,15 @@ fn main() {
                 if workspace == 0 { true } else { false },
             ));
 
+            if let Some(sort) = queries
+                .workspace_sort
+                .as_ref()
+                .and_then(|sort| sort.get(workspace))
+                .and_then(|sort| widget::SortMode::from_name(sort))
+            {
+                starting_workspaces[workspace].sort = sort;
+            }
+
             starting_items.push(Vec::new());
 
             let mut starting_index: usize = 0;
@@ -162,7 +197,7 @@ fn main() {
                         .get(workspace)
                         .unwrap())
             {
-                starting_items[workspace].push(widget::ItemState::new(
+                let mut item_state = widget::ItemState::new(
                     *queries
                         .clone()
                         .item_slot
@@ -190,7 +225,71 @@ fn main() {
                         .get(item)
                         .unwrap(),
                     if item == 0 { true } else { false },
-                ));
+                );
+
+                item_state.notes = queries
+                    .item_notes
+                    .as_ref()
+                    .and_then(|notes| notes.get(item))
+                    .cloned()
+                    .unwrap_or_default();
+                item_state.tags = queries
+                    .item_tags
+                    .as_ref()
+                    .and_then(|tags| tags.get(item))
+                    .cloned()
+                    .unwrap_or_default();
+                item_state.priority = queries
+                    .item_priority
+                    .as_ref()
+                    .and_then(|priority| priority.get(item))
+                    .map(|&priority| priority.min(widget::PRIORITY_NAMES.len() - 1))
+                    .unwrap_or_default();
+                item_state.created = queries
+                    .item_created
+                    .as_ref()
+                    .and_then(|created| created.get(item))
+                    .and_then(|created| widget::parse_datetime(created));
+                item_state.completed = queries
+                    .item_completed
+                    .as_ref()
+                    .and_then(|completed| completed.get(item))
+                    .and_then(|completed| widget::parse_datetime(completed));
+                // Whether the item is finished decides over a done status.
+                if let Some(status) = queries
+                    .item_status
+                    .as_ref()
+                    .and_then(|status| status.get(item))
+                    .and_then(|status| widget::ItemStatus::from_name(status))
+                    .filter(|&status| !item_state.is_finished && status != widget::ItemStatus::Done)
+                {
+                    item_state.status = status;
+                }
+
+                item_state.reminders = queries
+                    .item_reminders
+                    .as_ref()
+                    .and_then(|reminders| reminders.get(item))
+                    .and_then(|reminders| reminder::parse_offsets(reminders).ok())
+                    .flatten();
+                item_state.snoozed_until = queries
+                    .item_snoozed_until
+                    .as_ref()
+                    .and_then(|snoozed_until| snoozed_until.get(item))
+                    .and_then(|snoozed_until| widget::parse_datetime(snoozed_until));
+                item_state.deferrals = queries
+                    .item_deferrals
+                    .as_ref()
+                    .and_then(|deferrals| deferrals.get(item))
+                    .map(|deferrals| {
+                        deferrals
+                            .iter()
+                            .filter_map(|deferral| widget::parse_datetime(deferral))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                starting_items[workspace].push(item_state);
             }
         }
     }
@@ -205,6 +304,105 @@ fn main() {
         starting_items.push(Vec::new());
     }
 
+    (starting_workspaces, starting_items)
+}
+
+// Rebuilds an item written by `event::write_stored_items`.
+fn load_stored_item(stored: &query::StoredItem) -> widget::ItemState {
+    let mut item = widget::ItemState::new(
+        0,
+        stored.text.clone(),
+        stored.deadline.clone(),
+        stored.is_finished,
+        false,
+    );
+
+    item.notes = stored.notes.clone();
+    item.tags = stored.tags.clone();
+    item.priority = stored.priority.min(widget::PRIORITY_NAMES.len() - 1);
+    item.created = widget::parse_datetime(&stored.created);
+    item.completed = widget::parse_datetime(&stored.completed);
+    if let Some(status) = widget::ItemStatus::from_name(&stored.status)
+        .filter(|&status| !item.is_finished && status != widget::ItemStatus::Done)
+    {
+        item.status = status;
+    }
+    item.reminders = reminder::parse_offsets(&stored.reminders).ok().flatten();
+    item.snoozed_until = widget::parse_datetime(&stored.snoozed_until);
+    item.deferrals = stored
+        .deferrals
+        .iter()
+        .filter_map(|deferral| widget::parse_datetime(deferral))
+        .collect();
+
+    item
+}
+
+fn load_trashed_items(queries: &query::Queries) -> Vec<widget::TrashedItem> {
+    queries
+        .trash
+        .iter()
+        .flatten()
+        .map(|stored| widget::TrashedItem {
+            workspace: stored.workspace.clone(),
+            deleted: widget::parse_datetime(&stored.removed).unwrap_or_else(chrono::Local::now),
+            item: load_stored_item(stored),
+        })
+        .collect()
+}
+
+fn load_trashed_workspaces(queries: &query::Queries) -> Vec<widget::TrashedWorkspace> {
+    queries
+        .trash_workspaces
+        .iter()
+        .flatten()
+        .map(|stored| widget::TrashedWorkspace {
+            title: stored.title.clone(),
+            sort: widget::SortMode::from_name(&stored.sort).unwrap_or(widget::SortMode::Manual),
+            deleted: widget::parse_datetime(&stored.removed).unwrap_or_else(chrono::Local::now),
+            items: stored.items.iter().map(load_stored_item).collect(),
+        })
+        .collect()
+}
+
+fn load_archive(queries: &query::Queries) -> Vec<widget::ArchivedItem> {
+    queries
+        .archive
+        .iter()
+        .flatten()
+        .map(|stored| widget::ArchivedItem {
+            workspace: stored.workspace.clone(),
+            archived: widget::parse_datetime(&stored.removed).unwrap_or_else(chrono::Local::now),
+            item: load_stored_item(stored),
+        })
+        .collect()
+}
+
+fn main() {
+    better_panic::install();
+
+    let queries = QUERIES.clone();
+    lazy_static::initialize(&CONFIG);
+    lazy_static::initialize(&KEYMAP);
+    lazy_static::initialize(&THEME);
+
+    if let Some(query::Subcommand::Notify { daemon, interval }) = queries.command {
+        reminder::run(daemon, interval);
+        return;
+    }
+
+    let backend = CrosstermBackend::new(io::stdout());
+    let mut terminal = Terminal::new(backend).unwrap();
+
+    setup_panic_hook();
+    setup_terminal();
+
+    let request_redraw = REDRAW_REQUEST.0.clone();
+    let ui_events = setup_ui_events();
+    let ticker = tick(Duration::from_millis(250));
+
+    let (starting_workspaces, starting_items) = load_items(&queries);
+
     let app = Arc::new(Mutex::new(app::App {
         mode: app::Mode::DisplayItem,
         previous_mode: app::Mode::DisplayItem,
@@ -217,12 +415,42 @@ fn main() {
         edit_workspace: widget::EditWorkspaceState::new(),
         current_workspace: 0,
         is_modified: false,
-        help_mode: app::HelpMode::ItemHelp,
-        help_item: widget::HelpItemWidget {},
-        help_workspace: widget::HelpWorkspaceWidget {},
-        summary_scroll_state: Default::default(),
+        modified_at: None,
+        message: None,
+        help: widget::HelpState::new(),
+        confirm: widget::ConfirmState::new(),
+        search: widget::SearchState::new(),
+        move_item: None,
+        visual_anchor: None,
+        prompt: widget::PromptState::new(),
+        command_line: widget::CommandLineState::new(),
+        palette: widget::PaletteState::new(),
+        agenda: widget::AgendaState::new(),
+        calendar: widget::CalendarState::new(),
+        board: widget::BoardState::new(),
+        show_details: false,
+        show_snoozed: false,
+        archived: load_archive(&queries),
+        archive: widget::ArchiveState::new(),
+        trashed_items: load_trashed_items(&queries),
+        trashed_workspaces: load_trashed_workspaces(&queries),
+        trash: widget::TrashState::new(),
+        countdown: CONFIG.countdown,
+        toasts: Vec::new(),
+        reminders_checked_at: chrono::Local::now(),
+        needs_clear: false,
+        item_scroll: Default::default(),
+        workspace_scroll: Default::default(),
+        count: None,
+        pending_keys: Vec::new(),
+        item_areas: Vec::new(),
+        workspace_areas: Vec::new(),
+        has_sidebar: false,
     }));
 
+    event::archive_expired(&mut app.lock().unwrap());
+    event::purge_trash(&mut app.lock().unwrap());
+
     let move_app = app.clone();
 
     thread::spawn(move || {
@@ -250,15 +478,27 @@ fn main() {
                 let mut app = app.lock().unwrap();
 
                 match message.unwrap() {
-                    Event::Key(key_event) => {
+                    // Terminals that report key releases would otherwise
+                    // run every binding twice.
+                    Event::Key(key_event) if key_event.kind != KeyEventKind::Release => {
                         event::handle_key_bindings(app.mode, key_event, &mut app, &request_redraw);
                     }
+                    Event::Mouse(mouse_event) => {
+                        event::handle_mouse(mouse_event, &mut app, &request_redraw);
+                    }
+                    Event::Paste(text) => {
+                        event::handle_paste(&text, &mut app, &request_redraw);
+                    }
                     Event::Resize(..) => {
                         let _ = request_redraw.try_send(());
                     }
                     _ => {}
                 }
             }
+            recv(ticker) -> _ => {
+                let mut app = app.lock().unwrap();
+                event::handle_tick(&mut app, &request_redraw);
+            }
         }
     }
 }
@@ -11,6 +11,14 @@ use structopt::StructOpt;
 )]
 #[serde(default)]
 pub struct Queries {
+    #[structopt(subcommand)]
+    #[serde(skip)]
+    pub command: Option<Subcommand>,
+    /// Colour theme: dark, light, solarized, high-contrast, none or a theme
+    /// defined in the config file
+    #[structopt(long)]
+    #[serde(skip)]
+    pub theme: Option<String>,
     #[structopt(skip)]
     pub workspace_slot: Option<Vec<usize>>,
     #[structopt(skip)]
@@ -18,6 +26,8 @@ pub struct Queries {
     #[structopt(skip)]
     pub workspace_num_of_item: Option<Vec<usize>>,
     #[structopt(skip)]
+    pub workspace_sort: Option<Vec<String>>,
+    #[structopt(skip)]
     pub item_slot: Option<Vec<usize>>,
     #[structopt(skip)]
     pub item_text: Option<Vec<String>>,
@@ -25,9 +35,78 @@ pub struct Queries {
     pub item_expire_datetime_string: Option<Vec<String>>,
     #[structopt(skip)]
     pub item_is_finished: Option<Vec<bool>>,
+    #[structopt(skip)]
+    pub item_notes: Option<Vec<String>>,
+    #[structopt(skip)]
+    pub item_tags: Option<Vec<Vec<String>>>,
+    #[structopt(skip)]
+    pub item_priority: Option<Vec<usize>>,
+    #[structopt(skip)]
+    pub item_created: Option<Vec<String>>,
+    #[structopt(skip)]
+    pub item_completed: Option<Vec<String>>,
+    #[structopt(skip)]
+    pub item_status: Option<Vec<String>>,
+    #[structopt(skip)]
+    pub item_reminders: Option<Vec<String>>,
+    #[structopt(skip)]
+    pub item_snoozed_until: Option<Vec<String>>,
+    #[structopt(skip)]
+    pub item_deferrals: Option<Vec<Vec<String>>>,
+    #[structopt(skip)]
+    pub archive: Option<Vec<StoredItem>>,
+    #[structopt(skip)]
+    pub trash: Option<Vec<StoredItem>>,
+    #[structopt(skip)]
+    pub trash_workspaces: Option<Vec<StoredWorkspace>>,
+}
+
+// An item kept outside the workspaces with every field it had, the title of
+// the workspace it came from and when it was removed.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct StoredItem {
+    pub workspace: String,
+    pub removed: String,
+    pub text: String,
+    pub deadline: String,
+    pub is_finished: bool,
+    pub notes: String,
+    pub tags: Vec<String>,
+    pub priority: usize,
+    pub created: String,
+    pub completed: String,
+    pub status: String,
+    pub reminders: String,
+    pub snoozed_until: String,
+    pub deferrals: Vec<String>,
+}
+
+// A deleted workspace with its items.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct StoredWorkspace {
+    pub title: String,
+    pub sort: String,
+    pub removed: String,
+    pub items: Vec<StoredItem>,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+pub enum Subcommand {
+    /// Send the reminders due in the last interval, or keep sending them with
+    /// --daemon
+    Notify {
+        /// Keep running and check ~/.todo.yml every interval
+        #[structopt(long)]
+        daemon: bool,
+        /// Seconds between checks
+        #[structopt(long, default_value = "60")]
+        interval: u64,
+    },
 }
 
-fn get_items() -> Result<Queries, Error> {
+pub fn get_items() -> Result<Queries, Error> {
     let query_path = dirs_next::home_dir()
         .ok_or_else(|| format_err!("could not get home directory"))?
         .join(".todo.yml");
@@ -50,10 +129,23 @@ pub fn resolve_queries() -> Queries {
         queries.workspace_slot = item_queries.workspace_slot;
         queries.workspace_title = item_queries.workspace_title;
         queries.workspace_num_of_item = item_queries.workspace_num_of_item;
+        queries.workspace_sort = item_queries.workspace_sort;
         queries.item_slot = item_queries.item_slot;
         queries.item_text = item_queries.item_text;
         queries.item_expire_datetime_string = item_queries.item_expire_datetime_string;
         queries.item_is_finished = item_queries.item_is_finished;
+        queries.item_notes = item_queries.item_notes;
+        queries.item_tags = item_queries.item_tags;
+        queries.item_priority = item_queries.item_priority;
+        queries.item_created = item_queries.item_created;
+        queries.item_completed = item_queries.item_completed;
+        queries.item_status = item_queries.item_status;
+        queries.item_reminders = item_queries.item_reminders;
+        queries.item_snoozed_until = item_queries.item_snoozed_until;
+        queries.item_deferrals = item_queries.item_deferrals;
+        queries.archive = item_queries.archive;
+        queries.trash = item_queries.trash;
+        queries.trash_workspaces = item_queries.trash_workspaces;
     }
 
     queries
@@ -0,0 +1,225 @@
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration as StdDuration;
+
+use anyhow::{format_err, Error};
+use chrono::{DateTime, Duration, Local};
+
+use crate::app::App;
+use crate::widget::ItemState;
+use crate::{load_items, query, CONFIG};
+
+const UNITS: [(char, i64); 4] = [('w', 604800), ('d', 86400), ('h', 3600), ('m', 60)];
+
+// Parses how long before a deadline a reminder fires, such as `1d`, `1h30m`
+// or `0` for the deadline itself, into seconds.
+pub fn parse_offset(text: &str) -> Option<i64> {
+    if text == "0" {
+        return Some(0);
+    }
+
+    let mut seconds = 0;
+    let mut number = String::new();
+
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let &(_, size) = UNITS.iter().find(|&&(unit, _)| unit == c)?;
+        seconds += number.parse::<i64>().ok()? * size;
+        number.clear();
+    }
+
+    if number.is_empty() && !text.is_empty() {
+        Some(seconds)
+    } else {
+        None
+    }
+}
+
+pub fn format_offset(mut seconds: i64) -> String {
+    if seconds == 0 {
+        return String::from("0");
+    }
+
+    let mut text = String::new();
+
+    for &(unit, size) in UNITS.iter() {
+        if seconds >= size {
+            text.push_str(&format!("{}{}", seconds / size, unit));
+            seconds %= size;
+        }
+    }
+
+    text
+}
+
+// Reads the reminders of an item: empty for the configured ones, `none` for
+// no reminders, or offsets separated by spaces or commas.
+pub fn parse_offsets(text: &str) -> Result<Option<Vec<i64>>, Error> {
+    let text = text.trim();
+
+    if text.is_empty() {
+        return Ok(None);
+    }
+    if text == "none" {
+        return Ok(Some(Vec::new()));
+    }
+
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|offset| !offset.is_empty())
+        .map(|offset| {
+            parse_offset(offset).ok_or_else(|| format_err!("invalid reminder `{}`", offset))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+pub fn format_offsets(offsets: &Option<Vec<i64>>) -> String {
+    match offsets {
+        None => String::new(),
+        Some(offsets) if offsets.is_empty() => String::from("none"),
+        Some(offsets) => offsets
+            .iter()
+            .map(|&offset| format_offset(offset))
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+// The reminders of an item, falling back to the configured ones.
+pub fn item_offsets(item: &ItemState) -> Vec<i64> {
+    match &item.reminders {
+        Some(offsets) => offsets.clone(),
+        None => CONFIG
+            .reminders
+            .iter()
+            .filter_map(|offset| parse_offset(offset))
+            .collect(),
+    }
+}
+
+fn describe(workspace: &str, item: &ItemState, offset: i64) -> String {
+    if offset == 0 {
+        format!("{}: \"{}\" is due now", workspace, item.text)
+    } else {
+        format!(
+            "{}: \"{}\" is due in {}",
+            workspace,
+            item.text,
+            format_offset(offset)
+        )
+    }
+}
+
+// Messages for the reminders of unfinished items that fell in `(since, now]`.
+// Snoozed items stay quiet until they wake.
+pub fn due_reminders<'a>(
+    workspaces: impl Iterator<Item = (&'a str, &'a [ItemState])>,
+    since: DateTime<Local>,
+    now: DateTime<Local>,
+) -> Vec<String> {
+    let mut reminders = Vec::new();
+
+    for (title, items) in workspaces {
+        for item in items.iter() {
+            if item.is_finished || !item.has_expire_datetime || item.is_snoozed() {
+                continue;
+            }
+
+            for offset in item_offsets(item) {
+                let fires_at = item.expire_datetime - Duration::seconds(offset);
+
+                if fires_at > since && fires_at <= now {
+                    reminders.push(describe(title, item, offset));
+                }
+            }
+        }
+    }
+
+    reminders
+}
+
+// Runs the configured notification command, a program followed by its
+// arguments, with the message as its last argument, without waiting for it.
+fn notify(message: &str) -> Result<(), Error> {
+    let (program, args) = match CONFIG.notify_command.as_deref() {
+        Some([program, args @ ..]) => (program, args),
+        Some([]) => return Err(format_err!("notify_command is empty")),
+        None => return Ok(()),
+    };
+
+    let mut child = Command::new(program)
+        .args(args)
+        .arg(message)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format_err!("{}: {}", program, err))?;
+
+    thread::spawn(move || child.wait());
+
+    Ok(())
+}
+
+// Fires the reminders that came due since the last check while the TUI is
+// open.
+pub fn check(app: &mut App) -> bool {
+    let now = Local::now();
+    let reminders = due_reminders(
+        app.workspaces
+            .iter()
+            .map(|workspace| workspace.title.as_str())
+            .zip(app.items.iter().map(Vec::as_slice)),
+        app.reminders_checked_at,
+        now,
+    );
+    app.reminders_checked_at = now;
+
+    for reminder in reminders.iter() {
+        if let Err(err) = notify(reminder) {
+            app.set_message(format!("Could not notify: {}", err), true);
+        }
+        app.show_toast(reminder.clone());
+    }
+
+    !reminders.is_empty()
+}
+
+// `tdr notify`: sends the reminders of the last `interval` seconds, and with
+// `daemon` keeps checking ~/.todo.yml every `interval` seconds.
+pub fn run(daemon: bool, interval: u64) {
+    let mut since = Local::now() - Duration::seconds(interval as i64);
+
+    loop {
+        let now = Local::now();
+
+        if let Ok(queries) = query::get_items() {
+            let (workspaces, items) = load_items(&queries);
+            let reminders = due_reminders(
+                workspaces
+                    .iter()
+                    .map(|workspace| workspace.title.as_str())
+                    .zip(items.iter().map(Vec::as_slice)),
+                since,
+                now,
+            );
+
+            for reminder in reminders.iter() {
+                println!("{}", reminder);
+                if let Err(err) = notify(reminder) {
+                    eprintln!("tdr: could not notify: {}", err);
+                }
+            }
+        }
+        since = now;
+
+        if !daemon {
+            break;
+        }
+        thread::sleep(StdDuration::from_secs(interval));
+    }
+}
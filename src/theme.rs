@@ -1,12 +1,38 @@
-use tui::style::{Color, Style};
+use std::{env, process};
 
+use anyhow::{format_err, Error};
+use tui::style::{Color, Modifier, Style};
+
+use crate::config::{Config, ThemeConfig};
 use crate::THEME;
 
+// The active theme, which `:theme` can replace while running.
+pub fn theme() -> Theme {
+    *THEME.read().unwrap()
+}
+
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap() = theme;
+}
+
 #[inline]
 pub fn style() -> Style {
-    Style::default().bg(THEME.background)
+    Style::default().bg(theme().background)
+}
+
+// Style of selected and matched text. Themes without colours fall back to
+// reversed video, as dark text on a primary background would be invisible.
+pub fn highlight_style() -> Style {
+    let theme = theme();
+
+    if theme.text_dark == theme.text_primary {
+        style().add_modifier(Modifier::REVERSED)
+    } else {
+        style().fg(theme.text_dark).bg(theme.text_primary)
+    }
 }
 
+#[derive(Debug, Clone, Copy)]
 pub struct Theme {
     pub background: Color,
     pub unfinished: Color,
@@ -22,3 +48,198 @@ pub struct Theme {
     pub focused: Color,
     pub unfocused: Color,
 }
+
+pub const PRESETS: [&str; 5] = ["dark", "light", "solarized", "high-contrast", "none"];
+
+impl Theme {
+    pub fn preset(name: &str) -> Option<Theme> {
+        let theme = match name {
+            "dark" => Theme {
+                background: Color::Reset,
+                unfinished: Color::Red,
+                finished: Color::Green,
+                loss: Color::Red,
+                text_normal: Color::Reset,
+                text_primary: Color::Yellow,
+                text_secondary: Color::Cyan,
+                text_dark: Color::Black,
+                border_primary: Color::Blue,
+                border_secondary: Color::Reset,
+                border_axis: Color::Blue,
+                focused: Color::LightBlue,
+                unfocused: Color::DarkGray,
+            },
+            "light" => Theme {
+                background: Color::Reset,
+                unfinished: Color::Red,
+                finished: Color::Indexed(28),
+                loss: Color::Red,
+                text_normal: Color::Reset,
+                text_primary: Color::Indexed(130),
+                text_secondary: Color::Blue,
+                text_dark: Color::White,
+                border_primary: Color::Blue,
+                border_secondary: Color::Reset,
+                border_axis: Color::Blue,
+                focused: Color::Indexed(25),
+                unfocused: Color::Gray,
+            },
+            "solarized" => Theme {
+                background: Color::Rgb(0x00, 0x2b, 0x36),
+                unfinished: Color::Rgb(0xdc, 0x32, 0x2f),
+                finished: Color::Rgb(0x85, 0x99, 0x00),
+                loss: Color::Rgb(0xdc, 0x32, 0x2f),
+                text_normal: Color::Rgb(0x83, 0x94, 0x96),
+                text_primary: Color::Rgb(0xb5, 0x89, 0x00),
+                text_secondary: Color::Rgb(0x2a, 0xa1, 0x98),
+                text_dark: Color::Rgb(0x00, 0x2b, 0x36),
+                border_primary: Color::Rgb(0x26, 0x8b, 0xd2),
+                border_secondary: Color::Rgb(0x58, 0x6e, 0x75),
+                border_axis: Color::Rgb(0x26, 0x8b, 0xd2),
+                focused: Color::Rgb(0x26, 0x8b, 0xd2),
+                unfocused: Color::Rgb(0x58, 0x6e, 0x75),
+            },
+            "high-contrast" => Theme {
+                background: Color::Black,
+                unfinished: Color::LightRed,
+                finished: Color::LightGreen,
+                loss: Color::LightRed,
+                text_normal: Color::White,
+                text_primary: Color::LightYellow,
+                text_secondary: Color::LightCyan,
+                text_dark: Color::Black,
+                border_primary: Color::White,
+                border_secondary: Color::White,
+                border_axis: Color::White,
+                focused: Color::LightYellow,
+                unfocused: Color::Gray,
+            },
+            "none" => Theme {
+                background: Color::Reset,
+                unfinished: Color::Reset,
+                finished: Color::Reset,
+                loss: Color::Reset,
+                text_normal: Color::Reset,
+                text_primary: Color::Reset,
+                text_secondary: Color::Reset,
+                text_dark: Color::Reset,
+                border_primary: Color::Reset,
+                border_secondary: Color::Reset,
+                border_axis: Color::Reset,
+                focused: Color::Reset,
+                unfocused: Color::Reset,
+            },
+            _ => return None,
+        };
+
+        Some(theme)
+    }
+}
+
+// Parses a colour name, a 256-colour index or a `#rrggbb` value.
+pub fn parse_color(text: &str) -> Result<Color, Error> {
+    let color = match text
+        .to_lowercase()
+        .replace(&['-', '_', ' '][..], "")
+        .as_str()
+    {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        hex if hex.starts_with('#') && hex.len() == 7 => {
+            let component = |range| u8::from_str_radix(&hex[range], 16);
+            match (component(1..3), component(3..5), component(5..7)) {
+                (Ok(r), Ok(g), Ok(b)) => Color::Rgb(r, g, b),
+                _ => return Err(format_err!("invalid colour `{}`", text)),
+            }
+        }
+        index => match index.parse::<u8>() {
+            Ok(index) => Color::Indexed(index),
+            Err(_) => return Err(format_err!("invalid colour `{}`", text)),
+        },
+    };
+
+    Ok(color)
+}
+
+fn apply_theme_config(theme: &mut Theme, config: &ThemeConfig) -> Result<(), Error> {
+    let fields = [
+        (&mut theme.background, &config.background),
+        (&mut theme.unfinished, &config.unfinished),
+        (&mut theme.finished, &config.finished),
+        (&mut theme.loss, &config.loss),
+        (&mut theme.text_normal, &config.text_normal),
+        (&mut theme.text_primary, &config.text_primary),
+        (&mut theme.text_secondary, &config.text_secondary),
+        (&mut theme.text_dark, &config.text_dark),
+        (&mut theme.border_primary, &config.border_primary),
+        (&mut theme.border_secondary, &config.border_secondary),
+        (&mut theme.border_axis, &config.border_axis),
+        (&mut theme.focused, &config.focused),
+        (&mut theme.unfocused, &config.unfocused),
+    ];
+
+    for (color, value) in fields {
+        if let Some(value) = value {
+            *color = parse_color(value)?;
+        }
+    }
+
+    Ok(())
+}
+
+// Looks `name` up in the themes of the config file, then in the presets.
+pub fn get_theme(config: &Config, name: &str) -> Result<Theme, Error> {
+    match config.themes.get(name) {
+        Some(theme_config) => {
+            let base = theme_config.base.as_deref().unwrap_or("dark");
+            let mut theme = Theme::preset(base)
+                .ok_or_else(|| format_err!("theme `{}`: unknown base theme `{}`", name, base))?;
+
+            apply_theme_config(&mut theme, theme_config)
+                .map_err(|err| format_err!("theme `{}`: {}", name, err))?;
+
+            Ok(theme)
+        }
+        None => Theme::preset(name).ok_or_else(|| {
+            format_err!(
+                "unknown theme `{}`, expected one of {} or a theme from config.toml",
+                name,
+                PRESETS.join(", ")
+            )
+        }),
+    }
+}
+
+// An explicit `--theme` wins over `NO_COLOR`, which wins over the config file.
+pub fn resolve_theme(config: &Config, flag: Option<&str>) -> Theme {
+    let no_color = env::var_os("NO_COLOR").map_or(false, |value| !value.is_empty());
+
+    let name = match flag {
+        Some(name) => name,
+        None if no_color => "none",
+        None => config.theme.as_deref().unwrap_or("dark"),
+    };
+
+    match get_theme(config, name) {
+        Ok(theme) => theme,
+        Err(err) => {
+            eprintln!("tdr: {}", err);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use chrono::{Datelike, Duration, Local};
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::Modifier;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, StatefulWidget, Widget};
+use unicode_width::UnicodeWidthStr;
+
+use crate::draw::{add_padding, PaddingDirection};
+use crate::keymap::{Action, KeymapMode};
+use crate::theme::{highlight_style, style, theme};
+use crate::widget::{ItemState, WorkspaceState};
+use crate::KEYMAP;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum AgendaGroup {
+    Overdue,
+    Today,
+    Tomorrow,
+    ThisWeek,
+    Later,
+    NoDate,
+}
+
+impl AgendaGroup {
+    pub fn name(self) -> &'static str {
+        match self {
+            AgendaGroup::Overdue => "Overdue",
+            AgendaGroup::Today => "Today",
+            AgendaGroup::Tomorrow => "Tomorrow",
+            AgendaGroup::ThisWeek => "This week",
+            AgendaGroup::Later => "Later",
+            AgendaGroup::NoDate => "No date",
+        }
+    }
+
+    // Finished items only show up from today on, so the agenda does not fill
+    // up with the past.
+    fn of(item: &ItemState) -> Option<AgendaGroup> {
+        let now = Local::now();
+        let today = now.date().naive_local();
+
+        if !item.has_expire_datetime {
+            return if item.is_finished {
+                None
+            } else {
+                Some(AgendaGroup::NoDate)
+            };
+        }
+
+        let date = item.expire_datetime.date().naive_local();
+        let end_of_week = today + Duration::days(6 - today.weekday().num_days_from_monday() as i64);
+
+        if date < today && item.is_finished {
+            None
+        } else if item.expire_datetime < now && !item.is_finished {
+            Some(AgendaGroup::Overdue)
+        } else if date == today {
+            Some(AgendaGroup::Today)
+        } else if date == today.succ() {
+            Some(AgendaGroup::Tomorrow)
+        } else if date <= end_of_week {
+            Some(AgendaGroup::ThisWeek)
+        } else {
+            Some(AgendaGroup::Later)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AgendaEntry {
+    pub group: AgendaGroup,
+    pub workspace: usize,
+    pub index: usize,
+}
+
+// Items of every workspace by group, each group ordered by deadline. Snoozed
+// items are left out unless `show_snoozed` is set.
+pub fn agenda_entries(items: &[Vec<ItemState>], show_snoozed: bool) -> Vec<AgendaEntry> {
+    let mut entries: Vec<AgendaEntry> = items
+        .iter()
+        .enumerate()
+        .flat_map(|(workspace, items)| {
+            items
+                .iter()
+                .enumerate()
+                .filter(move |(_, item)| show_snoozed || !item.is_snoozed())
+                .filter_map(move |(index, item)| {
+                    AgendaGroup::of(item).map(|group| AgendaEntry {
+                        group,
+                        workspace,
+                        index,
+                    })
+                })
+        })
+        .collect();
+
+    entries.sort_by_key(|entry| {
+        (
+            entry.group,
+            items[entry.workspace][entry.index].expire_datetime,
+        )
+    });
+
+    entries
+}
+
+pub struct AgendaState {
+    pub selected: usize,
+    offset: usize,
+}
+
+impl AgendaState {
+    pub fn new() -> AgendaState {
+        AgendaState {
+            selected: 0,
+            offset: 0,
+        }
+    }
+}
+
+const DATE_FORMAT: &str = "%a %Y-%m-%d %H:%M";
+const MAX_WORKSPACE_WIDTH: usize = 20;
+
+pub struct AgendaWidget<'a> {
+    pub items: &'a [Vec<ItemState>],
+    pub workspaces: &'a [WorkspaceState],
+    pub show_snoozed: bool,
+}
+
+impl<'a> StatefulWidget for AgendaWidget<'a> {
+    type State = AgendaState;
+
+    fn render(self, mut area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style().fg(theme().border_primary))
+            .title(Span::styled(
+                KEYMAP.title(
+                    "Agenda",
+                    KeymapMode::Agenda,
+                    &[
+                        (&[Action::ToggleFinished], "toggle"),
+                        (&[Action::EditItem], "edit"),
+                        (&[Action::GoToItem], "go to item"),
+                        (&[Action::Close], "back"),
+                    ],
+                ),
+                style().fg(theme().text_normal),
+            ))
+            .style(style())
+            .render(area, buf);
+        area = add_padding(area, 1, PaddingDirection::All);
+        area = add_padding(area, 1, PaddingDirection::Left);
+        area = add_padding(area, 1, PaddingDirection::Right);
+
+        let entries = agenda_entries(self.items, self.show_snoozed);
+
+        if entries.is_empty() {
+            Paragraph::new(Span::styled("Nothing to do", style().fg(theme().unfocused)))
+                .render(area, buf);
+            return;
+        }
+
+        state.selected = state.selected.min(entries.len() - 1);
+
+        let workspace_width = self
+            .workspaces
+            .iter()
+            .map(|workspace| workspace.title.width())
+            .max()
+            .unwrap_or(0)
+            .min(MAX_WORKSPACE_WIDTH);
+        let width = area.width as usize;
+
+        // A header before the first entry of each group.
+        let mut lines = Vec::new();
+        let mut selected_lines = (0, 0);
+
+        for (position, entry) in entries.iter().enumerate() {
+            let top = lines.len();
+
+            if position == 0 || entries[position - 1].group != entry.group {
+                if position > 0 {
+                    lines.push(Spans::default());
+                }
+
+                let count = entries.iter().filter(|e| e.group == entry.group).count();
+                lines.push(Spans::from(Span::styled(
+                    format!("{} ({})", entry.group.name(), count),
+                    style()
+                        .fg(if entry.group == AgendaGroup::Overdue {
+                            theme().loss
+                        } else {
+                            theme().text_primary
+                        })
+                        .add_modifier(Modifier::BOLD),
+                )));
+            }
+
+            let item = &self.items[entry.workspace][entry.index];
+            let mark = if item.is_finished {
+                "✓"
+            } else if item.is_late {
+                "x"
+            } else {
+                "-"
+            };
+            let date = if item.has_expire_datetime {
+                item.expire_datetime.format(DATE_FORMAT).to_string()
+            } else {
+                String::new()
+            };
+            let workspace: String = self.workspaces[entry.workspace]
+                .title
+                .chars()
+                .take(MAX_WORKSPACE_WIDTH)
+                .collect();
+
+            let left = format!("  [{}] {}", mark, item.text);
+            let right = format!(
+                "  {:<workspace_width$}  {:<20}",
+                workspace,
+                date,
+                workspace_width = workspace_width
+            );
+            let gap = width.saturating_sub(left.width() + right.width());
+
+            // The header of a group scrolls into view with its first entry.
+            if position == state.selected {
+                selected_lines = (top, lines.len());
+            }
+
+            lines.push(Spans::from(Span::styled(
+                format!("{}{:gap$}{}", left, "", right, gap = gap),
+                if position == state.selected {
+                    highlight_style()
+                } else if item.is_finished {
+                    style().fg(theme().finished)
+                } else if item.is_late {
+                    style().fg(theme().loss)
+                } else {
+                    style().fg(theme().text_normal)
+                },
+            )));
+        }
+
+        let (top, bottom) = selected_lines;
+        let height = area.height as usize;
+        if top < state.offset {
+            state.offset = top;
+        } else if bottom >= state.offset + height {
+            state.offset = bottom + 1 - height;
+        }
+
+        Paragraph::new(lines)
+            .scroll((state.offset as u16, 0))
+            .render(area, buf);
+    }
+}
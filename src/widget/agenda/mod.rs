@@ -0,0 +1,3 @@
+mod agenda;
+
+pub use self::agenda::{agenda_entries, AgendaState, AgendaWidget};
@@ -0,0 +1,181 @@
+use chrono::{DateTime, Local};
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::Modifier;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, StatefulWidget, Widget};
+use unicode_width::UnicodeWidthStr;
+
+use crate::draw::{add_padding, PaddingDirection};
+use crate::keymap::{Action, KeymapMode};
+use crate::theme::{highlight_style, style, theme};
+use crate::widget::{find_matches, ItemState, SearchState};
+use crate::KEYMAP;
+
+// An item taken out of its workspace, which is remembered by title so that
+// it can be restored after workspaces were renamed or reordered.
+pub struct ArchivedItem {
+    pub workspace: String,
+    pub archived: DateTime<Local>,
+    pub item: ItemState,
+}
+
+pub struct ArchiveState {
+    pub selected: usize,
+    offset: usize,
+    pub search: SearchState,
+    pub is_searching: bool,
+}
+
+impl ArchiveState {
+    pub fn new() -> ArchiveState {
+        ArchiveState {
+            selected: 0,
+            offset: 0,
+            search: SearchState::new(),
+            is_searching: false,
+        }
+    }
+
+    // Indices into the archive of the items matching the search, the most
+    // recently archived first.
+    pub fn entries(&self, archived: &[ArchivedItem]) -> Vec<usize> {
+        let query = self.search.input.text();
+
+        (0..archived.len())
+            .rev()
+            .filter(|&index| {
+                let entry = &archived[index];
+
+                self.search.matches(&entry.item)
+                    || (!query.starts_with('#')
+                        && !find_matches(&entry.workspace, query).is_empty())
+            })
+            .collect()
+    }
+}
+
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+const MAX_WORKSPACE_WIDTH: usize = 20;
+
+pub struct ArchiveWidget<'a> {
+    pub archived: &'a [ArchivedItem],
+}
+
+impl<'a> StatefulWidget for ArchiveWidget<'a> {
+    type State = ArchiveState;
+
+    fn render(self, mut area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style().fg(theme().border_primary))
+            .title(Span::styled(
+                KEYMAP.title(
+                    "Archive",
+                    KeymapMode::Archive,
+                    &[
+                        (&[Action::Search], "search"),
+                        (&[Action::Restore], "restore"),
+                        (&[Action::Close], "back"),
+                    ],
+                ),
+                style().fg(theme().text_normal),
+            ))
+            .style(style())
+            .render(area, buf);
+        area = add_padding(area, 1, PaddingDirection::All);
+        area = add_padding(area, 1, PaddingDirection::Left);
+        area = add_padding(area, 1, PaddingDirection::Right);
+
+        if state.is_searching || state.search.is_active() {
+            let mut spans = vec![Span::styled("/", style().fg(theme().text_normal))];
+            if state.is_searching {
+                spans.extend(
+                    state.search.input.spans(
+                        area.width.saturating_sub(2),
+                        style()
+                            .add_modifier(Modifier::BOLD)
+                            .fg(theme().text_secondary),
+                    ),
+                );
+            } else {
+                spans.push(Span::styled(
+                    state.search.input.text().to_string(),
+                    style().fg(theme().text_secondary),
+                ));
+            }
+
+            Paragraph::new(Spans::from(spans)).render(area, buf);
+            area = add_padding(area, 2, PaddingDirection::Top);
+        }
+
+        let entries = state.entries(self.archived);
+
+        if entries.is_empty() {
+            Paragraph::new(Span::styled(
+                if self.archived.is_empty() {
+                    "Nothing archived"
+                } else {
+                    "No matching items"
+                },
+                style().fg(theme().unfocused),
+            ))
+            .render(area, buf);
+            return;
+        }
+
+        state.selected = state.selected.min(entries.len() - 1);
+
+        let height = area.height as usize;
+        if state.selected < state.offset {
+            state.offset = state.selected;
+        } else if state.selected >= state.offset + height {
+            state.offset = state.selected + 1 - height;
+        }
+
+        let workspace_width = entries
+            .iter()
+            .map(|&index| self.archived[index].workspace.width())
+            .max()
+            .unwrap_or(0)
+            .min(MAX_WORKSPACE_WIDTH);
+        let width = area.width as usize;
+
+        let lines: Vec<Spans> = entries
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .take(height)
+            .map(|(position, &index)| {
+                let entry = &self.archived[index];
+                let workspace: String = entry.workspace.chars().take(MAX_WORKSPACE_WIDTH).collect();
+
+                let left = format!(
+                    "[{}] {}",
+                    if entry.item.is_finished { "✓" } else { "-" },
+                    entry.item.text
+                );
+                let right = format!(
+                    "  {:<workspace_width$}  archived {}",
+                    workspace,
+                    entry.archived.format(DATE_FORMAT),
+                    workspace_width = workspace_width
+                );
+                let gap = width.saturating_sub(left.width() + right.width());
+
+                Spans::from(Span::styled(
+                    format!("{}{:gap$}{}", left, "", right, gap = gap),
+                    if position == state.selected {
+                        highlight_style()
+                    } else if entry.item.is_finished {
+                        style().fg(theme().finished)
+                    } else {
+                        style().fg(theme().text_normal)
+                    },
+                ))
+            })
+            .collect();
+
+        Paragraph::new(lines).render(area, buf);
+    }
+}
@@ -0,0 +1,3 @@
+mod archive;
+
+pub use self::archive::{ArchiveState, ArchiveWidget, ArchivedItem};
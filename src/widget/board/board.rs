@@ -0,0 +1,187 @@
+use tui::buffer::Buffer;
+use tui::layout::{Direction, Rect};
+use tui::style::Modifier;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, StatefulWidget, Widget};
+
+use crate::draw::{add_padding, split_evenly, PaddingDirection};
+use crate::keymap::{Action, KeymapMode};
+use crate::theme::{highlight_style, style, theme};
+use crate::widget::{ItemState, ItemStatus};
+use crate::{CONFIG, KEYMAP};
+
+// `visible` indices split by status, in the order of `ItemStatus::ALL`.
+pub fn board_columns(items: &[ItemState], visible: &[usize]) -> Vec<Vec<usize>> {
+    ItemStatus::ALL
+        .iter()
+        .map(|&status| {
+            visible
+                .iter()
+                .copied()
+                .filter(|&index| items[index].status == status)
+                .collect()
+        })
+        .collect()
+}
+
+pub fn wip_limit(status: ItemStatus) -> Option<usize> {
+    CONFIG.wip_limits.get(status.name()).copied()
+}
+
+// Each column keeps its own cursor and scroll offset.
+pub struct BoardState {
+    pub column: usize,
+    pub selected: [usize; 4],
+    offsets: [usize; 4],
+}
+
+impl BoardState {
+    pub fn new() -> BoardState {
+        BoardState {
+            column: 0,
+            selected: [0; 4],
+            offsets: [0; 4],
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.column = 0;
+        self.selected = [0; 4];
+        self.offsets = [0; 4];
+    }
+}
+
+pub struct BoardWidget<'a> {
+    pub title: &'a str,
+    pub items: &'a [ItemState],
+    pub visible: &'a [usize],
+}
+
+impl<'a> BoardWidget<'a> {
+    fn render_column(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        state: &mut BoardState,
+        column: usize,
+        indices: &[usize],
+    ) {
+        let status = ItemStatus::ALL[column];
+        let is_focused = column == state.column;
+        let limit = wip_limit(status);
+        let is_over_limit = limit.map_or(false, |limit| indices.len() > limit);
+
+        let header = match limit {
+            Some(limit) => format!(" {} {}/{} ", status.title(), indices.len(), limit),
+            None => format!(" {} {} ", status.title(), indices.len()),
+        };
+
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(if is_focused {
+                style().fg(theme().focused)
+            } else {
+                style().fg(theme().unfocused)
+            })
+            .title(Span::styled(
+                header,
+                if is_over_limit {
+                    style().fg(theme().loss).add_modifier(Modifier::BOLD)
+                } else {
+                    style().fg(theme().text_normal)
+                },
+            ))
+            .render(area, buf);
+        let area = add_padding(area, 1, PaddingDirection::All);
+        let height = area.height as usize;
+
+        if indices.is_empty() || height == 0 {
+            return;
+        }
+
+        let selected = state.selected[column].min(indices.len() - 1);
+        state.selected[column] = selected;
+
+        let offset = &mut state.offsets[column];
+        if selected < *offset {
+            *offset = selected;
+        } else if selected >= *offset + height {
+            *offset = selected + 1 - height;
+        }
+        let offset = (*offset).min(indices.len().saturating_sub(height));
+
+        let lines: Vec<Spans> = indices
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(height)
+            .map(|(row, &index)| {
+                let item = &self.items[index];
+                let mut text = String::new();
+
+                if item.priority > 0 {
+                    text.push_str(&"!".repeat(item.priority));
+                    text.push(' ');
+                }
+                text.push_str(&item.text);
+                if item.has_expire_datetime {
+                    text.push_str(&format!(" @{}", item.expire_datetime.format("%m-%d")));
+                }
+
+                Spans::from(Span::styled(
+                    format!("{:<width$}", text, width = area.width as usize),
+                    if is_focused && row == selected {
+                        highlight_style()
+                    } else if item.is_finished {
+                        style().fg(theme().finished)
+                    } else if item.is_late {
+                        style().fg(theme().loss)
+                    } else {
+                        style().fg(theme().text_normal)
+                    },
+                ))
+            })
+            .collect();
+
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+impl<'a> StatefulWidget for BoardWidget<'a> {
+    type State = BoardState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style().fg(theme().border_primary))
+            .title(Span::styled(
+                KEYMAP.title(
+                    self.title,
+                    KeymapMode::Board,
+                    &[
+                        (&[Action::PreviousColumn, Action::NextColumn], "column"),
+                        (
+                            &[Action::MoveToPreviousColumn, Action::MoveToNextColumn],
+                            "move item",
+                        ),
+                        (&[Action::GoToItem], "go to item"),
+                        (&[Action::Close], "back"),
+                    ],
+                ),
+                style().fg(theme().text_normal),
+            ))
+            .style(style())
+            .render(area, buf);
+
+        let columns = board_columns(self.items, self.visible);
+        let areas = split_evenly(
+            add_padding(area, 1, PaddingDirection::All),
+            ItemStatus::ALL.len() as u16,
+            Direction::Horizontal,
+        );
+
+        for (column, indices) in columns.iter().enumerate() {
+            self.render_column(areas[column], buf, state, column, indices);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+mod board;
+
+pub use self::board::{board_columns, wip_limit, BoardState, BoardWidget};
@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use tui::buffer::Buffer;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, StatefulWidget, Widget};
+use unicode_width::UnicodeWidthStr;
+
+use crate::draw::{add_padding, split_evenly, PaddingDirection};
+use crate::keymap::{Action, KeymapMode};
+use crate::theme::{highlight_style, style, theme};
+use crate::widget::{ItemState, WorkspaceState};
+use crate::KEYMAP;
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const WEEKS: u16 = 6;
+const DAY_LIST_HEIGHT: u16 = 10;
+
+fn first_of_month(year: i32, month: u32) -> NaiveDate {
+    NaiveDate::from_ymd(year, month, 1)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let next = if month == 12 {
+        first_of_month(year + 1, 1)
+    } else {
+        first_of_month(year, month + 1)
+    };
+
+    next.pred().day()
+}
+
+// Items of every workspace with a deadline on `date`, by deadline. Snoozed
+// items are left out unless `show_snoozed` is set.
+pub fn items_due_on(
+    items: &[Vec<ItemState>],
+    date: NaiveDate,
+    show_snoozed: bool,
+) -> Vec<(usize, usize)> {
+    let mut due: Vec<(usize, usize)> = items
+        .iter()
+        .enumerate()
+        .flat_map(|(workspace, items)| {
+            items
+                .iter()
+                .enumerate()
+                .filter(move |(_, item)| {
+                    item.has_expire_datetime
+                        && item.expire_datetime.date().naive_local() == date
+                        && (show_snoozed || !item.is_snoozed())
+                })
+                .map(move |(index, _)| (workspace, index))
+        })
+        .collect();
+
+    due.sort_by_key(|&(workspace, index)| items[workspace][index].expire_datetime);
+
+    due
+}
+
+// Late, pending and finished counts of the items due on each day.
+#[derive(Default, Clone, Copy)]
+struct DayCounts {
+    late: usize,
+    pending: usize,
+    finished: usize,
+}
+
+fn count_days(items: &[Vec<ItemState>], show_snoozed: bool) -> HashMap<NaiveDate, DayCounts> {
+    let mut days: HashMap<NaiveDate, DayCounts> = HashMap::new();
+
+    for item in items
+        .iter()
+        .flatten()
+        .filter(|item| item.has_expire_datetime && (show_snoozed || !item.is_snoozed()))
+    {
+        let counts = days
+            .entry(item.expire_datetime.date().naive_local())
+            .or_default();
+
+        if item.is_finished {
+            counts.finished += 1;
+        } else if item.is_late {
+            counts.late += 1;
+        } else {
+            counts.pending += 1;
+        }
+    }
+
+    days
+}
+
+pub struct CalendarState {
+    pub date: NaiveDate,
+    pub is_listing: bool,
+    pub selected: usize,
+}
+
+impl CalendarState {
+    pub fn new() -> CalendarState {
+        CalendarState {
+            date: Local::now().date().naive_local(),
+            is_listing: false,
+            selected: 0,
+        }
+    }
+
+    pub fn open(&mut self) {
+        self.date = Local::now().date().naive_local();
+        self.is_listing = false;
+    }
+
+    pub fn move_days(&mut self, days: i64) {
+        self.date = self.date + Duration::days(days);
+    }
+
+    // Keeps the day of the month where the other month has it.
+    pub fn move_months(&mut self, months: i32) {
+        let month = self.date.year() * 12 + self.date.month0() as i32 + months;
+        let (year, month) = (month.div_euclid(12), month.rem_euclid(12) as u32 + 1);
+        let day = self.date.day().min(days_in_month(year, month));
+
+        self.date = NaiveDate::from_ymd(year, month, day);
+    }
+
+    pub fn list(&mut self) {
+        self.is_listing = true;
+        self.selected = 0;
+    }
+}
+
+pub struct CalendarWidget<'a> {
+    pub items: &'a [Vec<ItemState>],
+    pub workspaces: &'a [WorkspaceState],
+    pub show_snoozed: bool,
+}
+
+impl<'a> CalendarWidget<'a> {
+    fn render_day(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        date: NaiveDate,
+        state: &CalendarState,
+        counts: Option<DayCounts>,
+    ) {
+        let is_selected = date == state.date;
+        let is_today = date == Local::now().date().naive_local();
+        let is_in_month = date.month() == state.date.month();
+
+        let title_style = if is_selected {
+            highlight_style()
+        } else if is_today {
+            style()
+                .fg(theme().text_primary)
+                .add_modifier(Modifier::BOLD)
+        } else if is_in_month {
+            style().fg(theme().text_normal)
+        } else {
+            style().fg(theme().unfocused)
+        };
+
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(if is_selected {
+                style().fg(theme().focused)
+            } else {
+                style().fg(theme().border_secondary)
+            })
+            .title(Span::styled(format!(" {} ", date.day()), title_style))
+            .render(area, buf);
+
+        let counts = match counts {
+            Some(counts) => counts,
+            None => return,
+        };
+
+        let mut spans = Vec::new();
+        let parts: [(usize, &str, Style); 3] = [
+            (counts.late, "x", style().fg(theme().loss)),
+            (counts.pending, "-", style().fg(theme().unfinished)),
+            (counts.finished, "✓", style().fg(theme().finished)),
+        ];
+
+        for (count, mark, part_style) in parts.iter() {
+            if *count > 0 {
+                spans.push(Span::styled(format!("{} {} ", count, mark), *part_style));
+            }
+        }
+
+        Paragraph::new(Spans::from(spans)).render(add_padding(area, 1, PaddingDirection::All), buf);
+    }
+
+    fn render_grid(&self, area: Rect, buf: &mut Buffer, state: &CalendarState) {
+        let days = count_days(self.items, self.show_snoozed);
+        let first = first_of_month(state.date.year(), state.date.month());
+        let start = first - Duration::days(first.weekday().num_days_from_monday() as i64);
+
+        let header = Rect { height: 1, ..area };
+        let rows = split_evenly(
+            add_padding(area, 1, PaddingDirection::Top),
+            WEEKS,
+            Direction::Vertical,
+        );
+        let columns = |row: Rect| split_evenly(row, 7, Direction::Horizontal);
+
+        for (column, name) in columns(header).into_iter().zip(WEEKDAYS.iter()) {
+            Paragraph::new(Span::styled(
+                format!(" {}", name),
+                style().fg(theme().text_secondary),
+            ))
+            .render(column, buf);
+        }
+
+        for week in 0..WEEKS as usize {
+            for (weekday, cell) in columns(rows[week]).into_iter().enumerate() {
+                let date = start + Duration::days((week * 7 + weekday) as i64);
+                self.render_day(cell, buf, date, state, days.get(&date).copied());
+            }
+        }
+    }
+
+    fn render_day_list(&self, area: Rect, buf: &mut Buffer, state: &mut CalendarState) {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style().fg(theme().focused))
+            .title(Span::styled(
+                KEYMAP.title(
+                    &state.date.format("%A %Y-%m-%d").to_string(),
+                    KeymapMode::CalendarDay,
+                    &[
+                        (&[Action::ToggleFinished], "toggle"),
+                        (&[Action::EditItem], "edit"),
+                        (&[Action::GoToItem], "go to item"),
+                        (&[Action::Close], "back"),
+                    ],
+                ),
+                style().fg(theme().text_normal),
+            ))
+            .render(area, buf);
+        let area = add_padding(area, 1, PaddingDirection::All);
+        let area = add_padding(area, 1, PaddingDirection::Left);
+
+        let due = items_due_on(self.items, state.date, self.show_snoozed);
+
+        if due.is_empty() {
+            Paragraph::new(Span::styled("Nothing due", style().fg(theme().unfocused)))
+                .render(area, buf);
+            return;
+        }
+
+        state.selected = state.selected.min(due.len() - 1);
+        let offset = (state.selected + 1).saturating_sub(area.height as usize);
+        let width = area.width as usize;
+
+        let lines: Vec<Spans> = due
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(area.height as usize)
+            .map(|(position, &(workspace, index))| {
+                let item = &self.items[workspace][index];
+                let mark = if item.is_finished {
+                    "✓"
+                } else if item.is_late {
+                    "x"
+                } else {
+                    "-"
+                };
+
+                let left = format!(
+                    "[{}] {} {}",
+                    mark,
+                    item.expire_datetime.format("%H:%M"),
+                    item.text
+                );
+                let right = format!(" {} ", self.workspaces[workspace].title);
+                let gap = width.saturating_sub(left.width() + right.width());
+
+                Spans::from(Span::styled(
+                    format!("{}{:gap$}{}", left, "", right, gap = gap),
+                    if position == state.selected {
+                        highlight_style()
+                    } else if item.is_finished {
+                        style().fg(theme().finished)
+                    } else if item.is_late {
+                        style().fg(theme().loss)
+                    } else {
+                        style().fg(theme().text_normal)
+                    },
+                ))
+            })
+            .collect();
+
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+impl<'a> StatefulWidget for CalendarWidget<'a> {
+    type State = CalendarState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let layout = Layout::default()
+            .constraints(
+                [
+                    Constraint::Min(0),
+                    Constraint::Length(if state.is_listing { DAY_LIST_HEIGHT } else { 0 }),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+
+        let title = if state.is_listing {
+            format!(" {} ", state.date.format("%B %Y"))
+        } else {
+            KEYMAP.title(
+                &state.date.format("%B %Y").to_string(),
+                KeymapMode::Calendar,
+                &[
+                    (
+                        &[
+                            Action::PreviousDay,
+                            Action::NextWeek,
+                            Action::PreviousWeek,
+                            Action::NextDay,
+                        ],
+                        "move",
+                    ),
+                    (&[Action::PreviousMonth, Action::NextMonth], "month"),
+                    (&[Action::GoToToday], "today"),
+                    (&[Action::ListDay], "list day"),
+                    (&[Action::Close], "back"),
+                ],
+            )
+        };
+
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style().fg(theme().border_primary))
+            .title(Span::styled(title, style().fg(theme().text_normal)))
+            .style(style())
+            .render(layout[0], buf);
+        self.render_grid(add_padding(layout[0], 1, PaddingDirection::All), buf, state);
+
+        if state.is_listing {
+            self.render_day_list(layout[1], buf, state);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+mod calendar;
+
+pub use self::calendar::{items_due_on, CalendarState, CalendarWidget};
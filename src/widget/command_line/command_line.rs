@@ -0,0 +1,138 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use tui::buffer::Buffer;
+use tui::layout::{Alignment, Rect};
+use tui::style::Modifier;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders};
+use tui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::command;
+use crate::keymap::{Action, KeymapMode};
+use crate::theme::{highlight_style, style, theme};
+use crate::widget::InputState;
+use crate::KEYMAP;
+
+// Candidates offered by completion, which replace the text from `start` on.
+struct Completion {
+    start: usize,
+    candidates: Vec<String>,
+    index: usize,
+}
+
+pub struct CommandLineState {
+    pub input: InputState,
+    completion: Option<Completion>,
+}
+
+impl CommandLineState {
+    pub fn new() -> CommandLineState {
+        CommandLineState {
+            input: InputState::new(),
+            completion: None,
+        }
+    }
+
+    pub fn handle_key(&mut self, keycode: KeyCode, modifiers: KeyModifiers) -> bool {
+        self.completion = None;
+        self.input.handle_key(keycode, modifiers)
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        self.completion = None;
+        self.input.insert_str(text);
+    }
+
+    // Fills in the first of `candidates`, or steps through the ones offered
+    // by the previous completion.
+    pub fn complete(&mut self, start: usize, candidates: Vec<String>, forward: bool) {
+        let completion = match self.completion.take() {
+            Some(mut completion) => {
+                let len = completion.candidates.len();
+                completion.index = if forward {
+                    (completion.index + 1) % len
+                } else {
+                    (completion.index + len - 1) % len
+                };
+                completion
+            }
+            None if candidates.is_empty() => return,
+            None => Completion {
+                start,
+                candidates,
+                index: 0,
+            },
+        };
+
+        let mut text = self.input.text()[..completion.start].to_string();
+        text.push_str(&completion.candidates[completion.index]);
+        self.input.set_text(text);
+
+        if completion.candidates.len() > 1 {
+            self.completion = Some(completion);
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.completion = None;
+    }
+}
+
+pub struct CommandLineWidget {}
+
+impl StatefulWidget for CommandLineWidget {
+    type State = CommandLineState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let mut spans = vec![Span::styled(": ", style().fg(theme().text_normal))];
+        spans.extend(
+            state.input.spans(
+                area.width.saturating_sub(4),
+                style()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(theme().text_secondary),
+            ),
+        );
+
+        let title = match (&state.completion, command::usage(state.input.text())) {
+            (Some(completion), _) => {
+                let mut title = vec![Span::raw(" ")];
+                for (index, candidate) in completion.candidates.iter().enumerate() {
+                    title.push(Span::styled(
+                        candidate.clone(),
+                        if index == completion.index {
+                            highlight_style()
+                        } else {
+                            style().fg(theme().text_normal)
+                        },
+                    ));
+                    title.push(Span::raw(" "));
+                }
+                Spans::from(title)
+            }
+            (None, Some(usage)) => Spans::from(Span::styled(
+                format!(" {} ", usage),
+                style().fg(theme().text_normal),
+            )),
+            (None, None) => Spans::from(Span::styled(
+                KEYMAP.title(
+                    "Command",
+                    KeymapMode::CommandLine,
+                    &[(&[Action::Complete], "complete")],
+                ),
+                style().fg(theme().text_normal),
+            )),
+        };
+
+        Paragraph::new(Spans::from(spans))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(style().fg(theme().border_primary))
+                    .title(title),
+            )
+            .style(style())
+            .alignment(Alignment::Left)
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,3 @@
+mod command_line;
+
+pub use self::command_line::{CommandLineState, CommandLineWidget};
@@ -0,0 +1,92 @@
+use tui::buffer::Buffer;
+use tui::layout::{Alignment, Rect};
+use tui::style::Modifier;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear};
+use tui::widgets::{Paragraph, StatefulWidget, Widget, Wrap};
+
+use crate::keymap::{Action, KeymapMode};
+use crate::theme::{style, theme};
+use crate::KEYMAP;
+
+fn title() -> String {
+    KEYMAP.title(
+        "Confirm",
+        KeymapMode::Confirm,
+        &[(&[Action::Submit], "yes"), (&[Action::Cancel], "no")],
+    )
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ConfirmAction {
+    DeleteWorkspace,
+    DeleteItems,
+    Quit,
+}
+
+pub struct ConfirmState {
+    pub message: String,
+    pub action: Option<ConfirmAction>,
+}
+
+impl ConfirmState {
+    pub fn new() -> ConfirmState {
+        ConfirmState {
+            message: String::new(),
+            action: None,
+        }
+    }
+
+    pub fn ask(&mut self, action: ConfirmAction, message: String) {
+        self.action = Some(action);
+        self.message = message;
+    }
+
+    pub fn reset(&mut self) {
+        self.message.drain(..);
+        self.action = None;
+    }
+
+    pub fn get_rect(&self, area: Rect) -> Rect {
+        let width =
+            (self.message.chars().count().max(title().chars().count()) as u16 + 6).min(area.width);
+        let height = 5.min(area.height);
+
+        Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        }
+    }
+}
+
+pub struct ConfirmWidget {}
+
+impl StatefulWidget for ConfirmWidget {
+    type State = ConfirmState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Clear.render(area, buf);
+
+        Paragraph::new(vec![
+            Spans::from(""),
+            Spans::from(Span::styled(
+                &state.message,
+                style()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(theme().text_primary),
+            )),
+        ])
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(style().fg(theme().loss))
+                .title(Span::styled(title(), style().fg(theme().text_normal))),
+        )
+        .style(style())
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .render(area, buf);
+    }
+}
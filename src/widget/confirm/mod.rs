@@ -0,0 +1,3 @@
+mod confirm;
+
+pub use self::confirm::{ConfirmAction, ConfirmState, ConfirmWidget};
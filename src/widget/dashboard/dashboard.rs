@@ -0,0 +1,403 @@
+use chrono::{Duration, Local, NaiveDate};
+use tui::buffer::Buffer;
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::Modifier;
+use tui::symbols;
+use tui::text::{Span, Spans};
+use tui::widgets::{BarChart, Block, Borders, Gauge, LineGauge, Paragraph, Sparkline, Widget};
+use unicode_width::UnicodeWidthStr;
+
+use crate::draw::{add_padding, PaddingDirection};
+use crate::keymap::{Action, KeymapMode};
+use crate::theme::{style, theme};
+use crate::widget::{ItemState, WorkspaceState};
+use crate::KEYMAP;
+
+// Days shown by the completion charts.
+const SPARKLINE_DAYS: i64 = 60;
+const BAR_CHART_DAYS: i64 = 30;
+const COUNTS_WIDTH: u16 = 46;
+
+#[derive(Default, Clone, Copy)]
+struct Counts {
+    pending: usize,
+    finished: usize,
+    late: usize,
+}
+
+impl Counts {
+    fn of<'a>(items: impl Iterator<Item = &'a ItemState>) -> Counts {
+        let mut counts = Counts::default();
+
+        for item in items {
+            if item.is_finished {
+                counts.finished += 1;
+            } else if item.is_late {
+                counts.late += 1;
+            } else {
+                counts.pending += 1;
+            }
+        }
+
+        counts
+    }
+
+    fn total(self) -> usize {
+        self.pending + self.finished + self.late
+    }
+
+    fn ratio(self) -> f64 {
+        if self.total() == 0 {
+            0.0
+        } else {
+            self.finished as f64 / self.total() as f64
+        }
+    }
+}
+
+// Items finished on each of the last `days` days, oldest first.
+fn completed_per_day(items: &[Vec<ItemState>], days: i64) -> Vec<u64> {
+    let today = Local::now().date().naive_local();
+    let first = today - Duration::days(days - 1);
+    let mut counts = vec![0; days as usize];
+
+    for completed in items.iter().flatten().filter_map(|item| item.completed) {
+        let date = completed.date().naive_local();
+
+        if date >= first && date <= today {
+            counts[(date - first).whole_days() as usize] += 1;
+        }
+    }
+
+    counts
+}
+
+fn block(title: &str) -> Block<'static> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(style().fg(theme().border_axis))
+        .title(Span::styled(
+            format!(" {} ", title),
+            style().fg(theme().text_normal),
+        ))
+}
+
+pub struct DashboardWidget<'a> {
+    pub items: &'a [Vec<ItemState>],
+    pub workspaces: &'a [WorkspaceState],
+}
+
+impl<'a> DashboardWidget<'a> {
+    fn counts_line(&self, title: &str, counts: Counts) -> Spans<'static> {
+        let title: String = title.chars().take(20).collect();
+        let gap = 20usize.saturating_sub(title.width());
+
+        Spans::from(vec![
+            Span::styled(
+                format!("{}{:gap$}", title, "", gap = gap),
+                style().fg(theme().text_normal),
+            ),
+            Span::styled(
+                format!("{:>8}", counts.pending),
+                style().fg(theme().unfinished),
+            ),
+            Span::styled(
+                format!("{:>8}", counts.finished),
+                style().fg(theme().finished),
+            ),
+            Span::styled(format!("{:>8}", counts.late), style().fg(theme().loss)),
+        ])
+    }
+
+    fn render_counts(&self, area: Rect, buf: &mut Buffer) {
+        block("Workspaces").render(area, buf);
+        let area = add_padding(
+            add_padding(area, 1, PaddingDirection::All),
+            1,
+            PaddingDirection::Left,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(COUNTS_WIDTH), Constraint::Min(0)].as_ref())
+            .split(area);
+
+        let mut lines = vec![Spans::from(Span::styled(
+            format!("{:<20}{:>8}{:>8}{:>8}", "", "pending", "done", "late"),
+            style().fg(theme().text_secondary),
+        ))];
+        let rows = area.height.saturating_sub(3) as usize;
+
+        for (workspace, items) in self.workspaces.iter().zip(self.items.iter()).take(rows) {
+            let counts = Counts::of(items.iter());
+            let row = Rect {
+                y: layout[1].y + lines.len() as u16,
+                height: 1,
+                ..layout[1]
+            };
+
+            lines.push(self.counts_line(&workspace.title, counts));
+            LineGauge::default()
+                .ratio(counts.ratio())
+                .label(Span::styled(
+                    format!("{:>4.0}%", counts.ratio() * 100.0),
+                    style().fg(theme().text_normal),
+                ))
+                .line_set(symbols::line::THICK)
+                .gauge_style(style().fg(theme().finished).bg(theme().background))
+                .render(row, buf);
+        }
+
+        let total = Counts::of(self.items.iter().flatten());
+        lines.push(Spans::default());
+        let mut total_line = self.counts_line("Total", total);
+        for span in total_line.0.iter_mut() {
+            span.style = span.style.add_modifier(Modifier::BOLD);
+        }
+        lines.push(total_line);
+
+        Gauge::default()
+            .ratio(total.ratio())
+            .label(Span::styled(
+                format!("{} of {} done", total.finished, total.total()),
+                style().fg(theme().text_normal),
+            ))
+            .gauge_style(style().fg(theme().finished).bg(theme().background))
+            .render(
+                Rect {
+                    y: layout[1].y + lines.len() as u16 - 1,
+                    height: 1,
+                    ..layout[1]
+                },
+                buf,
+            );
+
+        Paragraph::new(lines).render(layout[0], buf);
+    }
+
+    fn render_sparkline(&self, area: Rect, buf: &mut Buffer) {
+        // Running total, so the line climbs with every finished item.
+        let data: Vec<u64> = completed_per_day(self.items, SPARKLINE_DAYS)
+            .into_iter()
+            .scan(0, |total, count| {
+                *total += count;
+                Some(*total)
+            })
+            .collect();
+
+        let inner_width = area.width.saturating_sub(2) as usize;
+        let data = &data[data.len().saturating_sub(inner_width)..];
+
+        Sparkline::default()
+            .block(block(&format!(
+                "Completed over the last {} days",
+                SPARKLINE_DAYS
+            )))
+            .data(data)
+            .style(style().fg(theme().finished))
+            .render(area, buf);
+    }
+
+    fn render_bar_chart(&self, area: Rect, buf: &mut Buffer) {
+        let first = Local::now().date().naive_local() - Duration::days(BAR_CHART_DAYS - 1);
+        let counts = completed_per_day(self.items, BAR_CHART_DAYS);
+
+        // As many of the latest days as there is room for.
+        let bar_width = 2;
+        let room = (area.width.saturating_sub(2) / (bar_width + 1)) as usize;
+        let skip = counts.len().saturating_sub(room);
+
+        let labels: Vec<String> = (0..counts.len())
+            .map(|day| {
+                (first + Duration::days(day as i64))
+                    .format("%d")
+                    .to_string()
+            })
+            .collect();
+        let data: Vec<(&str, u64)> = labels
+            .iter()
+            .map(String::as_str)
+            .zip(counts.iter().copied())
+            .skip(skip)
+            .collect();
+
+        BarChart::default()
+            .block(block(&format!(
+                "Completed per day, last {} days",
+                BAR_CHART_DAYS
+            )))
+            .data(&data)
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .bar_style(style().fg(theme().finished))
+            .value_style(style().fg(theme().background).bg(theme().finished))
+            .label_style(style().fg(theme().border_axis))
+            .render(area, buf);
+    }
+
+    fn render_list(
+        &self,
+        area: Rect,
+        buf: &mut Buffer,
+        title: &str,
+        lines: Vec<(String, &ItemState, usize)>,
+    ) {
+        block(title).render(area, buf);
+        let area = add_padding(
+            add_padding(area, 1, PaddingDirection::All),
+            1,
+            PaddingDirection::Left,
+        );
+
+        if lines.is_empty() {
+            Paragraph::new(Span::styled("None", style().fg(theme().unfocused))).render(area, buf);
+            return;
+        }
+
+        let lines: Vec<Spans> = lines
+            .into_iter()
+            .take(area.height as usize)
+            .map(|(date, item, workspace)| {
+                Spans::from(vec![
+                    Span::styled(format!("{}  ", date), style().fg(theme().text_secondary)),
+                    Span::styled(
+                        item.text.clone(),
+                        if item.is_late {
+                            style().fg(theme().loss)
+                        } else {
+                            style().fg(theme().text_normal)
+                        },
+                    ),
+                    Span::styled(
+                        format!("  {}", self.workspaces[workspace].title),
+                        style().fg(theme().unfocused),
+                    ),
+                ])
+            })
+            .collect();
+
+        Paragraph::new(lines).render(area, buf);
+    }
+
+    fn open_items(&self) -> Vec<(&ItemState, usize)> {
+        self.items
+            .iter()
+            .enumerate()
+            .flat_map(|(workspace, items)| items.iter().map(move |item| (item, workspace)))
+            .filter(|(item, _)| !item.is_finished)
+            .collect()
+    }
+}
+
+impl<'a> Widget for DashboardWidget<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style().fg(theme().border_primary))
+            .title(Span::styled(
+                KEYMAP.title(
+                    "Dashboard",
+                    KeymapMode::Dashboard,
+                    &[(&[Action::Close], "back")],
+                ),
+                style().fg(theme().text_normal),
+            ))
+            .style(style())
+            .render(area, buf);
+        let area = add_padding(area, 1, PaddingDirection::All);
+
+        let layout = Layout::default()
+            .constraints(
+                [
+                    Constraint::Length((self.workspaces.len() as u16 + 5).min(area.height / 3)),
+                    Constraint::Length(10),
+                    Constraint::Min(0),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+
+        self.render_counts(layout[0], buf);
+
+        let charts = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+            .split(layout[1]);
+        self.render_sparkline(charts[0], buf);
+        self.render_bar_chart(charts[1], buf);
+
+        let lists = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(layout[2]);
+
+        let mut oldest = self.open_items();
+        oldest.retain(|(item, _)| item.created.is_some());
+        oldest.sort_by_key(|(item, _)| item.created);
+        self.render_list(
+            lists[0],
+            buf,
+            "Oldest open items",
+            oldest
+                .into_iter()
+                .map(|(item, workspace)| {
+                    let date = item.created.map(|created| created.date().naive_local());
+                    (format_date(date), item, workspace)
+                })
+                .collect(),
+        );
+
+        let now = Local::now();
+        let mut upcoming = self.open_items();
+        upcoming.retain(|(item, _)| item.has_expire_datetime && item.expire_datetime >= now);
+        upcoming.sort_by_key(|(item, _)| item.expire_datetime);
+        self.render_list(
+            lists[1],
+            buf,
+            "Upcoming deadlines",
+            upcoming
+                .into_iter()
+                .map(|(item, workspace)| {
+                    (
+                        item.expire_datetime.format("%Y-%m-%d %H:%M").to_string(),
+                        item,
+                        workspace,
+                    )
+                })
+                .collect(),
+        );
+    }
+}
+
+fn format_date(date: Option<NaiveDate>) -> String {
+    date.map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished(days_ago: i64) -> ItemState {
+        let mut item = ItemState::new(0, String::new(), String::new(), true, false);
+        item.completed = Some(Local::now() - Duration::days(days_ago));
+        item
+    }
+
+    #[test]
+    fn completed_per_day_counts_each_day_oldest_first() {
+        let items = vec![
+            vec![finished(0), finished(0), finished(2)],
+            vec![finished(6), finished(7)],
+            vec![ItemState::new(
+                0,
+                String::new(),
+                String::new(),
+                false,
+                false,
+            )],
+        ];
+
+        assert_eq!(completed_per_day(&items, 7), vec![1, 0, 0, 0, 1, 0, 2]);
+    }
+}
@@ -0,0 +1,165 @@
+use chrono::{DateTime, Local};
+use tui::buffer::Buffer;
+use tui::layout::{Alignment, Rect};
+use tui::style::Modifier;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, Widget, Wrap};
+
+use crate::draw::{add_padding, PaddingDirection};
+use crate::reminder;
+use crate::theme::{style, theme};
+use crate::widget::{ItemState, ItemStatus, DATETIME_FORMAT, PRIORITY_NAMES};
+
+fn format_datetime(datetime: Option<DateTime<Local>>) -> String {
+    datetime
+        .map(|datetime| datetime.format(DATETIME_FORMAT).to_string())
+        .unwrap_or_else(|| String::from("-"))
+}
+
+// Every field of the selected item, wrapped to the width of the pane.
+pub struct DetailWidget<'a> {
+    pub item: Option<&'a ItemState>,
+}
+
+impl<'a> Widget for DetailWidget<'a> {
+    fn render(self, mut area: Rect, buf: &mut Buffer) {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style().fg(theme().border_primary))
+            .title(Span::styled(" Details ", style().fg(theme().text_normal)))
+            .render(area, buf);
+        area = add_padding(area, 1, PaddingDirection::All);
+        area = add_padding(area, 1, PaddingDirection::Left);
+        area = add_padding(area, 1, PaddingDirection::Right);
+
+        let item = match self.item {
+            Some(item) => item,
+            None => {
+                Paragraph::new(Span::styled(
+                    "No item selected",
+                    style().fg(theme().unfocused),
+                ))
+                .style(style())
+                .render(area, buf);
+                return;
+            }
+        };
+
+        let label_style = style().fg(theme().text_secondary);
+        let value_style = style().fg(theme().text_normal);
+        let field = |label: &str, value: String, value_style| {
+            Spans::from(vec![
+                Span::styled(format!("{:<10}", label), label_style),
+                Span::styled(value, value_style),
+            ])
+        };
+
+        let (status, status_style) = if item.is_finished {
+            ("finished", style().fg(theme().finished))
+        } else if item.is_late {
+            ("late", style().fg(theme().loss))
+        } else {
+            ("unfinished", value_style)
+        };
+        let status = match item.status {
+            ItemStatus::InProgress | ItemStatus::Blocked => {
+                format!("{}, {}", status, item.status.title().to_lowercase())
+            }
+            _ => status.to_string(),
+        };
+
+        let mut lines = vec![
+            Spans::from(Span::styled(
+                item.text.clone(),
+                value_style.add_modifier(Modifier::BOLD),
+            )),
+            Spans::default(),
+            field("Status", status, status_style),
+            field(
+                "Priority",
+                PRIORITY_NAMES[item.priority].to_string(),
+                value_style,
+            ),
+            field(
+                "Deadline",
+                if item.has_expire_datetime {
+                    item.expire_datetime.format(DATETIME_FORMAT).to_string()
+                } else {
+                    String::from("-")
+                },
+                if item.is_late && !item.is_finished {
+                    style().fg(theme().loss)
+                } else {
+                    value_style
+                },
+            ),
+            field(
+                "Reminders",
+                match reminder::item_offsets(item) {
+                    offsets if offsets.is_empty() => String::from("-"),
+                    offsets => reminder::format_offsets(&Some(offsets)),
+                },
+                value_style,
+            ),
+            field(
+                "Snoozed",
+                match item.snoozed_until.filter(|_| item.is_snoozed()) {
+                    Some(until) => format!("until {}", until.format(DATETIME_FORMAT)),
+                    None => String::from("-"),
+                },
+                value_style,
+            ),
+            field(
+                "Deferred",
+                match item.deferrals.len() {
+                    0 => String::from("never"),
+                    1 => format!(
+                        "once, last {}",
+                        format_datetime(item.deferrals.last().copied())
+                    ),
+                    n => format!(
+                        "{} times, last {}",
+                        n,
+                        format_datetime(item.deferrals.last().copied())
+                    ),
+                },
+                if item.deferrals.len() > 2 {
+                    style().fg(theme().loss)
+                } else {
+                    value_style
+                },
+            ),
+            field("Created", format_datetime(item.created), value_style),
+            field("Completed", format_datetime(item.completed), value_style),
+            field(
+                "Tags",
+                if item.tags.is_empty() {
+                    String::from("-")
+                } else {
+                    item.tags
+                        .iter()
+                        .map(|tag| format!("#{}", tag))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                },
+                label_style,
+            ),
+        ];
+
+        if !item.notes.is_empty() {
+            lines.push(Spans::default());
+            lines.push(Spans::from(Span::styled("Notes", label_style)));
+            lines.extend(
+                item.notes
+                    .lines()
+                    .map(|line| Spans::from(Span::styled(line.to_string(), value_style))),
+            );
+        }
+
+        Paragraph::new(lines)
+            .style(style())
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,3 @@
+mod detail;
+
+pub use self::detail::DetailWidget;
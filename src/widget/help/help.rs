@@ -0,0 +1,223 @@
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders};
+use tui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::command::COMMANDS;
+use crate::draw::{add_padding, PaddingDirection};
+use crate::keymap::{Action, KeymapMode};
+use crate::theme::{style, theme};
+use crate::KEYMAP;
+
+pub const HELP_WIDTH: usize = 63;
+// Longer lists of keys push their own description along rather than every
+// line's.
+const MAX_KEY_WIDTH: usize = 18;
+
+// Keys outside of the keymap.
+const ITEM_TEXT: &[(&str, &[(&str, &str)])] = &[(
+    "Counts:",
+    &[("<5j>, <2]l>", "Repeat a motion; <10G> goes to row 10")],
+)];
+
+const WORKSPACE_TEXT: &[(&str, &[(&str, &str)])] = &[(
+    "Text Input:",
+    &[
+        ("<Left>, <Right>", "Move cursor"),
+        ("<Alt-b>, <Alt-f>", "Move cursor by word"),
+        ("<Home>, <End>", "Start/end of line (<Ctrl-a>/<Ctrl-e>)"),
+        ("<Delete>", "Delete character under cursor"),
+        ("<Ctrl-w>, <Alt-d>", "Delete word before/after cursor"),
+        ("<Ctrl-u>, <Ctrl-k>", "Delete to start/end of line"),
+    ],
+)];
+
+// The views and prompts reached from each page, after its display.
+const ITEM_MODES: &[KeymapMode] = &[
+    KeymapMode::CommandLine,
+    KeymapMode::Palette,
+    KeymapMode::ItemPrompt,
+    KeymapMode::Search,
+    KeymapMode::MoveItem,
+    KeymapMode::Agenda,
+    KeymapMode::Calendar,
+    KeymapMode::CalendarDay,
+    KeymapMode::Board,
+    KeymapMode::Dashboard,
+    KeymapMode::Archive,
+    KeymapMode::Trash,
+];
+
+const WORKSPACE_MODES: &[KeymapMode] = &[KeymapMode::Prompt, KeymapMode::Confirm, KeymapMode::Help];
+
+fn keymap_section(mode: KeymapMode) -> (String, Vec<(String, String)>) {
+    let bindings = KEYMAP
+        .help(mode)
+        .into_iter()
+        .map(|(action, keys)| {
+            let keys: Vec<String> = keys.iter().map(|key| format!("<{}>", key.name())).collect();
+            (keys.join(", "), action.description().to_string())
+        })
+        .collect();
+
+    (mode.title().to_string(), bindings)
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum HelpPage {
+    Item,
+    Workspace,
+}
+
+pub struct HelpState {
+    pub page: HelpPage,
+    pub scroll: u16,
+}
+
+impl HelpState {
+    pub fn new() -> HelpState {
+        HelpState {
+            page: HelpPage::Item,
+            scroll: 0,
+        }
+    }
+
+    pub fn open(&mut self, page: HelpPage) {
+        self.page = page;
+        self.scroll = 0;
+    }
+
+    pub fn next_page(&mut self) {
+        self.page = match self.page {
+            HelpPage::Item => HelpPage::Workspace,
+            HelpPage::Workspace => HelpPage::Item,
+        };
+        self.scroll = 0;
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll = self.scroll.saturating_add(1);
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    // The keymap sections of the page come from the active bindings, so they
+    // always match what the keys do.
+    fn sections(&self) -> Vec<(String, Vec<(String, String)>)> {
+        let (mode, modes, text) = match self.page {
+            HelpPage::Item => (KeymapMode::Item, ITEM_MODES, ITEM_TEXT),
+            HelpPage::Workspace => (KeymapMode::Workspace, WORKSPACE_MODES, WORKSPACE_TEXT),
+        };
+
+        let mut sections = vec![keymap_section(mode)];
+
+        if self.page == HelpPage::Item {
+            sections.push((
+                "Commands (after <:>):".to_string(),
+                COMMANDS
+                    .iter()
+                    .map(|&(name, _, description)| (format!(":{}", name), description.to_string()))
+                    .collect(),
+            ));
+        }
+
+        sections.extend(modes.iter().map(|&mode| keymap_section(mode)));
+
+        for (title, lines) in text {
+            sections.push((
+                title.to_string(),
+                lines
+                    .iter()
+                    .map(|(keys, description)| (keys.to_string(), description.to_string()))
+                    .collect(),
+            ));
+        }
+
+        sections
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let sections = self.sections();
+
+        let key_width = sections
+            .iter()
+            .flat_map(|(_, lines)| lines.iter().map(|(keys, _)| keys.chars().count()))
+            .max()
+            .unwrap_or(0)
+            .min(MAX_KEY_WIDTH);
+
+        let mut text = Vec::new();
+
+        for (title, lines) in sections {
+            text.push(String::new());
+            text.push(title);
+            for (keys, description) in lines {
+                text.push(format!(
+                    "{:width$} {}",
+                    keys,
+                    description,
+                    width = key_width
+                ));
+            }
+        }
+
+        text
+    }
+
+    pub fn get_rect(&self, area: Rect) -> Rect {
+        let width = (HELP_WIDTH as u16).min(area.width);
+        let height = (self.lines().len() as u16 + 4).min(area.height);
+
+        Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        }
+    }
+}
+
+pub struct HelpWidget {}
+
+impl StatefulWidget for HelpWidget {
+    type State = HelpState;
+
+    fn render(self, mut area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let title = KEYMAP.title(
+            match state.page {
+                HelpPage::Item => "Help - Item",
+                HelpPage::Workspace => "Help - Workspace",
+            },
+            KeymapMode::Help,
+            &[
+                (&[Action::NextPage], "next page"),
+                (&[Action::ScrollDown, Action::ScrollUp], "scroll"),
+            ],
+        );
+
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style().fg(theme().border_primary))
+            .title(Span::styled(title, style().fg(theme().text_normal)))
+            .render(area, buf);
+        area = add_padding(area, 1, PaddingDirection::All);
+        area = add_padding(area, 2, PaddingDirection::Left);
+
+        let lines = state.lines();
+
+        let max_scroll = (lines.len() as u16).saturating_sub(area.height);
+        state.scroll = state.scroll.min(max_scroll);
+
+        let text: Vec<_> = lines
+            .into_iter()
+            .map(|line| Spans::from(Span::styled(line, style().fg(theme().text_normal))))
+            .collect();
+
+        Paragraph::new(text)
+            .scroll((state.scroll, 0))
+            .render(area, buf);
+    }
+}
@@ -1,8 +1,3 @@
-mod item;
-mod workspace;
+mod help;
 
-pub const HELP_WIDTH: usize = 63;
-pub const HELP_HEIGHT: usize = 23;
-
-pub use self::item::HelpItemWidget;
-pub use self::workspace::HelpWorkspaceWidget;
+pub use self::help::{HelpPage, HelpState, HelpWidget, HELP_WIDTH};
@@ -0,0 +1,184 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use tui::style::{Modifier, Style};
+use tui::text::Span;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// A single line of editable text with a cursor, shared by every prompt.
+pub struct InputState {
+    text: String,
+    // Byte offset of the cursor, always on a grapheme boundary.
+    cursor: usize,
+}
+
+impl InputState {
+    pub fn new() -> InputState {
+        InputState {
+            text: String::new(),
+            cursor: 0,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn set_text(&mut self, text: String) {
+        self.text = text;
+        self.cursor = self.text.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+    }
+
+    pub fn insert_str(&mut self, text: &str) {
+        let text: String = text
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+
+        self.text.insert_str(self.cursor, &text);
+        self.cursor += text.len();
+    }
+
+    // Applies an editing key, returning whether it was handled.
+    pub fn handle_key(&mut self, keycode: KeyCode, modifiers: KeyModifiers) -> bool {
+        match (modifiers, keycode) {
+            (KeyModifiers::NONE, KeyCode::Char(c)) | (KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                let mut buf = [0; 4];
+                self.insert_str(c.encode_utf8(&mut buf));
+            }
+            (KeyModifiers::NONE, KeyCode::Backspace)
+            | (KeyModifiers::CONTROL, KeyCode::Char('h')) => {
+                let start = self.prev_boundary(self.cursor);
+                self.delete(start, self.cursor);
+            }
+            (KeyModifiers::NONE, KeyCode::Delete) => {
+                let end = self.next_boundary(self.cursor);
+                self.delete(self.cursor, end);
+            }
+            (KeyModifiers::NONE, KeyCode::Left) | (KeyModifiers::CONTROL, KeyCode::Char('b')) => {
+                self.cursor = self.prev_boundary(self.cursor);
+            }
+            (KeyModifiers::NONE, KeyCode::Right) | (KeyModifiers::CONTROL, KeyCode::Char('f')) => {
+                self.cursor = self.next_boundary(self.cursor);
+            }
+            (KeyModifiers::NONE, KeyCode::Home) | (KeyModifiers::CONTROL, KeyCode::Char('a')) => {
+                self.cursor = 0;
+            }
+            (KeyModifiers::NONE, KeyCode::End) | (KeyModifiers::CONTROL, KeyCode::Char('e')) => {
+                self.cursor = self.text.len();
+            }
+            (KeyModifiers::ALT, KeyCode::Char('b')) | (KeyModifiers::CONTROL, KeyCode::Left) => {
+                self.cursor = self.word_start(self.cursor);
+            }
+            (KeyModifiers::ALT, KeyCode::Char('f')) | (KeyModifiers::CONTROL, KeyCode::Right) => {
+                self.cursor = self.word_end(self.cursor);
+            }
+            (KeyModifiers::ALT, KeyCode::Char('d')) => {
+                let end = self.word_end(self.cursor);
+                self.delete(self.cursor, end);
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('w')) => {
+                let mut start = self.cursor;
+                while start > 0 && self.grapheme_before(start).trim().is_empty() {
+                    start = self.prev_boundary(start);
+                }
+                while start > 0 && !self.grapheme_before(start).trim().is_empty() {
+                    start = self.prev_boundary(start);
+                }
+                self.delete(start, self.cursor);
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('u')) => {
+                self.delete(0, self.cursor);
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('k')) => {
+                self.delete(self.cursor, self.text.len());
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    // Renders the text scrolled so that the cursor fits in `width` columns,
+    // with the cursor cell reversed.
+    pub fn spans(&self, width: u16, style: Style) -> Vec<Span<'static>> {
+        let width = width as usize;
+
+        let before: Vec<&str> = self.text[..self.cursor].graphemes(true).collect();
+        let mut after = self.text[self.cursor..].graphemes(true);
+        let cursor = after.next().unwrap_or(" ");
+
+        let mut used = cursor.width().max(1);
+        let mut start = before.len();
+        while start > 0 && used + before[start - 1].width() <= width {
+            used += before[start - 1].width();
+            start -= 1;
+        }
+
+        let mut rest = String::new();
+        for grapheme in after {
+            if used + grapheme.width() > width {
+                break;
+            }
+            used += grapheme.width();
+            rest.push_str(grapheme);
+        }
+
+        vec![
+            Span::styled(before[start..].concat(), style),
+            Span::styled(cursor.to_string(), style.add_modifier(Modifier::REVERSED)),
+            Span::styled(rest, style),
+        ]
+    }
+
+    fn delete(&mut self, start: usize, end: usize) {
+        self.text.replace_range(start..end, "");
+        self.cursor = start;
+    }
+
+    fn grapheme_before(&self, pos: usize) -> &str {
+        self.text[..pos].graphemes(true).next_back().unwrap_or("")
+    }
+
+    fn prev_boundary(&self, pos: usize) -> usize {
+        pos - self.grapheme_before(pos).len()
+    }
+
+    fn next_boundary(&self, pos: usize) -> usize {
+        pos + self.text[pos..].graphemes(true).next().map_or(0, str::len)
+    }
+
+    fn is_word(grapheme: &str) -> bool {
+        grapheme.chars().any(char::is_alphanumeric)
+    }
+
+    fn word_start(&self, mut pos: usize) -> usize {
+        while pos > 0 && !InputState::is_word(self.grapheme_before(pos)) {
+            pos = self.prev_boundary(pos);
+        }
+        while pos > 0 && InputState::is_word(self.grapheme_before(pos)) {
+            pos = self.prev_boundary(pos);
+        }
+        pos
+    }
+
+    fn word_end(&self, mut pos: usize) -> usize {
+        let grapheme_at = |pos: usize| self.text[pos..].graphemes(true).next().unwrap_or("");
+
+        while pos < self.text.len() && !InputState::is_word(grapheme_at(pos)) {
+            pos = self.next_boundary(pos);
+        }
+        while pos < self.text.len() && InputState::is_word(grapheme_at(pos)) {
+            pos = self.next_boundary(pos);
+        }
+        pos
+    }
+}
@@ -0,0 +1,3 @@
+mod input;
+
+pub use self::input::InputState;
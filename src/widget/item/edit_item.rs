@@ -1,16 +1,17 @@
+use crossterm::event::{KeyCode, KeyModifiers};
 use tui::buffer::Buffer;
 use tui::layout::{Alignment, Rect};
 use tui::style::Modifier;
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders};
-use tui::widgets::{Paragraph, StatefulWidget, Widget, Wrap};
+use tui::widgets::{Paragraph, StatefulWidget, Widget};
 
-use crate::theme::style;
-use crate::THEME;
+use crate::theme::{style, theme};
+use crate::widget::InputState;
 
 pub struct EditItemState {
-    pub input_string: String,
-    pub input_datetime: String,
+    pub input: InputState,
+    pub input_datetime: InputState,
     has_input: bool,
     pub has_expire_datetime: bool,
     error_msg: Option<String>,
@@ -19,46 +20,54 @@ pub struct EditItemState {
 impl EditItemState {
     pub fn new() -> EditItemState {
         EditItemState {
-            input_string: String::new(),
-            input_datetime: String::new(),
+            input: InputState::new(),
+            input_datetime: InputState::new(),
             has_input: false,
             has_expire_datetime: false,
-            error_msg: Some(String::new()),
+            error_msg: None,
         }
     }
 
-    pub fn add_char(&mut self, c: char) {
+    fn current_input(&mut self) -> &mut InputState {
         if self.has_expire_datetime {
-            self.input_datetime.push(c);
+            &mut self.input_datetime
         } else {
-            self.input_string.push(c);
+            &mut self.input
         }
-        self.has_input = true;
     }
 
-    pub fn del_char(&mut self) {
-        if self.has_expire_datetime {
-            self.input_datetime.pop();
-        } else {
-            self.input_string.pop();
-        }
+    pub fn handle_key(&mut self, keycode: KeyCode, modifiers: KeyModifiers) -> bool {
+        let handled = self.current_input().handle_key(keycode, modifiers);
+        self.has_input |= handled;
+        handled
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        self.current_input().insert_str(text);
+        self.has_input = true;
     }
 
     pub fn reset(&mut self) {
-        self.input_datetime.drain(..);
-        self.input_string.drain(..);
+        self.input_datetime.clear();
+        self.input.clear();
         self.has_input = false;
         self.error_msg = None;
     }
 
-    pub fn enter(&mut self, slot: usize) -> super::ItemState {
-        super::ItemState::new(
-            slot,
-            self.input_string.clone(),
-            self.input_datetime.clone(),
-            false,
-            true,
-        )
+    pub fn open(&mut self, item: &super::ItemState) {
+        self.reset();
+        self.input.set_text(item.text.clone());
+        self.input_datetime
+            .set_text(item.expire_datetime_string.clone());
+    }
+
+    // Applies the edited text and deadline, keeping the rest of the item.
+    pub fn enter(&mut self, item: &mut super::ItemState) {
+        if !self.input.is_empty() {
+            item.text = self.input.text().to_string();
+        }
+
+        item.set_expire_datetime(self.input_datetime.text().to_string());
     }
 }
 
@@ -70,45 +79,49 @@ impl StatefulWidget for EditItemWidget {
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         let spans = if !state.has_input && state.error_msg.is_some() {
             Spans::from(vec![
-                Span::styled("> ", style().fg(THEME.text_normal)),
+                Span::styled("> ", style().fg(theme().text_normal)),
                 Span::styled(
                     state.error_msg.as_ref().unwrap(),
-                    style().add_modifier(Modifier::BOLD).fg(THEME.loss),
+                    style().add_modifier(Modifier::BOLD).fg(theme().loss),
                 ),
             ])
         } else {
-            Spans::from(vec![
-                Span::styled("> ", style().fg(THEME.text_normal)),
-                Span::styled(
-                    if state.has_expire_datetime {
-                        &state.input_datetime
-                    } else {
-                        &state.input_string
-                    },
+            let input = if state.has_expire_datetime {
+                &state.input_datetime
+            } else {
+                &state.input
+            };
+            let mut spans = vec![Span::styled("> ", style().fg(theme().text_normal))];
+            spans.extend(
+                input.spans(
+                    area.width.saturating_sub(4),
                     style()
                         .add_modifier(Modifier::BOLD)
-                        .fg(THEME.text_secondary),
+                        .fg(theme().text_secondary),
                 ),
-            ])
+            );
+            Spans::from(spans)
         };
         Paragraph::new(spans)
             .block(if state.has_expire_datetime {
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(style().fg(THEME.border_primary))
+                    .border_style(style().fg(theme().border_primary))
                     .title(Span::styled(
                         " Modify Deadline ",
-                        style().fg(THEME.text_normal),
+                        style().fg(theme().text_normal),
                     ))
             } else {
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(style().fg(THEME.border_primary))
-                    .title(Span::styled(" Modify Item ", style().fg(THEME.text_normal)))
+                    .border_style(style().fg(theme().border_primary))
+                    .title(Span::styled(
+                        " Modify Item ",
+                        style().fg(theme().text_normal),
+                    ))
             })
             .style(style())
             .alignment(Alignment::Left)
-            .wrap(Wrap { trim: true })
             .render(area, buf);
     }
 }
@@ -1,13 +1,18 @@
-use chrono::{offset::TimeZone, DateTime, Local, NaiveDateTime};
+use chrono::{
+    offset::TimeZone, DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime,
+};
 use regex::Regex;
 use tui::buffer::Buffer;
 use tui::layout::{Alignment, Rect};
+use tui::style::{Modifier, Style};
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, Paragraph, StatefulWidget, Widget};
 
+use crate::config::Countdown;
 use crate::draw::{add_padding, PaddingDirection};
-use crate::theme::style;
-use crate::THEME;
+use crate::reminder;
+use crate::theme::{highlight_style, style, theme};
+use crate::widget::find_matches;
 
 pub struct ItemState {
     pub slot: usize,
@@ -18,6 +23,135 @@ pub struct ItemState {
     pub is_finished: bool,
     pub is_late: bool,
     pub is_selected: bool,
+    pub is_marked: bool,
+    pub notes: String,
+    pub tags: Vec<String>,
+    pub priority: usize,
+    pub created: Option<DateTime<Local>>,
+    pub completed: Option<DateTime<Local>>,
+    pub status: ItemStatus,
+    // Seconds before the deadline to remind at, or None for the configured
+    // reminders.
+    pub reminders: Option<Vec<i64>>,
+    pub snoozed_until: Option<DateTime<Local>>,
+    // When the item was snoozed, oldest first.
+    pub deferrals: Vec<DateTime<Local>>,
+}
+
+// Progress of an item on the board. `Done` goes together with `is_finished`.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ItemStatus {
+    Todo,
+    InProgress,
+    Blocked,
+    Done,
+}
+
+impl ItemStatus {
+    pub const ALL: [ItemStatus; 4] = [
+        ItemStatus::Todo,
+        ItemStatus::InProgress,
+        ItemStatus::Blocked,
+        ItemStatus::Done,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ItemStatus::Todo => "todo",
+            ItemStatus::InProgress => "in_progress",
+            ItemStatus::Blocked => "blocked",
+            ItemStatus::Done => "done",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            ItemStatus::Todo => "Todo",
+            ItemStatus::InProgress => "In progress",
+            ItemStatus::Blocked => "Blocked",
+            ItemStatus::Done => "Done",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ItemStatus> {
+        ItemStatus::ALL
+            .iter()
+            .copied()
+            .find(|status| status.name() == name)
+    }
+}
+
+pub const PRIORITY_NAMES: [&str; 4] = ["none", "low", "medium", "high"];
+
+pub const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+pub fn parse_datetime(text: &str) -> Option<DateTime<Local>> {
+    NaiveDateTime::parse_from_str(text, DATETIME_FORMAT)
+        .ok()
+        .and_then(|datetime| Local.from_local_datetime(&datetime).single())
+}
+
+// Parses a deadline in one of the `year-month-date hour:minute:second`,
+// `year-month-date` or `hour:minute:second` formats.
+fn parse_expire_datetime(text: &str) -> Option<DateTime<Local>> {
+    let datetime = if Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap().is_match(text) {
+        NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .ok()?
+            .and_hms(0, 0, 0)
+    } else if Regex::new(r"^\d{2}:\d{2}:\d{2}$").unwrap().is_match(text) {
+        Local::now()
+            .date()
+            .naive_local()
+            .and_time(NaiveTime::parse_from_str(text, "%H:%M:%S").ok()?)
+    } else if Regex::new(r"^\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}$")
+        .unwrap()
+        .is_match(text)
+    {
+        NaiveDateTime::parse_from_str(text, DATETIME_FORMAT).ok()?
+    } else {
+        return None;
+    };
+
+    Local.from_local_datetime(&datetime).single()
+}
+
+const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+// Parses when a snoozed item resurfaces: `+1h` or another reminder offset from
+// now, `tomorrow` or a weekday for the start of that day, or a deadline
+// timestamp.
+pub fn parse_snooze(text: &str) -> Option<DateTime<Local>> {
+    let text = text.trim().to_lowercase();
+    let now = Local::now();
+
+    if let Some(offset) = text.strip_prefix('+') {
+        return Some(now + Duration::seconds(reminder::parse_offset(offset)?));
+    }
+
+    let today = now.date().naive_local();
+    let days = if text == "tomorrow" {
+        1
+    } else if let Some(weekday) = WEEKDAYS
+        .iter()
+        .position(|&name| text.len() >= 3 && name.starts_with(text.as_str()))
+    {
+        // The next such day, a week ahead when it is today.
+        (weekday as i64 - today.weekday().num_days_from_monday() as i64 + 6) % 7 + 1
+    } else {
+        return parse_expire_datetime(&text).filter(|&datetime| datetime > now);
+    };
+
+    Local
+        .from_local_datetime(&(today + Duration::days(days)).and_hms(0, 0, 0))
+        .single()
 }
 
 impl ItemState {
@@ -28,123 +162,207 @@ impl ItemState {
         is_finished: bool,
         is_selected: bool,
     ) -> ItemState {
-        let input_datetime = if Regex::new(r"^\d{4}-\d{2}-\d{2}$")
-            .unwrap()
-            .is_match(&expire_datetime_string)
-        {
-            format!("{}-04:00 00:00:00", expire_datetime_string)
-        } else if Regex::new(r"^\d{2}:\d{2}:\d{2}$")
-            .unwrap()
-            .is_match(&expire_datetime_string)
-        {
-            format!("{} {}", Local::now().date(), expire_datetime_string)
-        } else if Regex::new(r"^\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}$")
-            .unwrap()
-            .is_match(&expire_datetime_string)
-        {
-            expire_datetime_string.replace(" ", "-04:00 ")
-        } else {
-            "".to_string()
-        };
-
-        let has_expire_datetime = {
-            if !input_datetime.is_empty() {
-                true
+        let mut item = ItemState {
+            slot,
+            text,
+            has_expire_datetime: false,
+            expire_datetime_string: String::new(),
+            expire_datetime: Local::now(),
+            is_finished,
+            is_late: false,
+            is_selected,
+            is_marked: false,
+            notes: String::new(),
+            tags: Vec::new(),
+            priority: 0,
+            created: Some(Local::now()),
+            completed: if is_finished {
+                Some(Local::now())
             } else {
-                false
-            }
+                None
+            },
+            status: if is_finished {
+                ItemStatus::Done
+            } else {
+                ItemStatus::Todo
+            },
+            reminders: None,
+            snoozed_until: None,
+            deferrals: Vec::new(),
         };
 
-        let expire_datetime: DateTime<Local> = {
-            if has_expire_datetime {
-                Local
-                    .from_local_datetime(
-                        &NaiveDateTime::parse_from_str(&input_datetime, "%Y-%m-%d-04:00 %H:%M:%S")
-                            .unwrap(),
-                    )
-                    .unwrap()
-            } else {
-                Local::now()
+        item.set_expire_datetime(expire_datetime_string);
+
+        item
+    }
+
+    pub fn set_expire_datetime(&mut self, expire_datetime_string: String) {
+        match parse_expire_datetime(&expire_datetime_string) {
+            Some(expire_datetime) => {
+                self.has_expire_datetime = true;
+                self.expire_datetime = expire_datetime;
+                self.is_late = (expire_datetime - Local::now()).whole_seconds() <= 0;
             }
-        };
+            None => {
+                self.has_expire_datetime = false;
+                self.expire_datetime = Local::now();
+                self.is_late = false;
+            }
+        }
 
-        let is_late = {
-            if !input_datetime.is_empty() {
-                if (expire_datetime - Local::now()).whole_seconds() > 0 {
-                    false
-                } else {
-                    true
-                }
+        self.expire_datetime_string = expire_datetime_string;
+    }
+
+    // Marks the item finished or not, remembering when it was finished. An
+    // unfinished item leaves the done column for the todo one.
+    pub fn set_finished(&mut self, is_finished: bool) {
+        if is_finished != self.is_finished {
+            self.completed = if is_finished {
+                Some(Local::now())
             } else {
-                false
-            }
-        };
+                None
+            };
+        }
 
-        ItemState {
-            slot,
-            text,
-            has_expire_datetime,
-            expire_datetime_string,
-            expire_datetime,
-            is_finished,
-            is_late,
-            is_selected,
+        self.is_finished = is_finished;
+
+        if is_finished {
+            self.status = ItemStatus::Done;
+        } else if self.status == ItemStatus::Done {
+            self.status = ItemStatus::Todo;
         }
     }
 
-    fn get_time_offset(&mut self) -> String {
-        let offset = self.expire_datetime - Local::now();
-        let mut second = offset.whole_seconds();
+    pub fn is_snoozed(&self) -> bool {
+        self.snoozed_until
+            .map_or(false, |until| until > Local::now())
+    }
 
-        if offset.whole_seconds() < 0 {
-            self.is_late = true;
+    // Hides the item until `until`, recording the deferral. With
+    // `push_deadline`, the deadline moves back by as long as the item sleeps.
+    pub fn snooze(&mut self, until: DateTime<Local>, push_deadline: bool) {
+        let now = Local::now();
+
+        if push_deadline && self.has_expire_datetime {
+            let deadline = self.expire_datetime + (until - now);
+            self.set_expire_datetime(deadline.format(DATETIME_FORMAT).to_string());
         }
 
-        let minute: i64;
-        let hour: i64;
-        let day: i64;
-        let week: i64;
+        self.snoozed_until = Some(until);
+        self.deferrals.push(now);
+    }
+
+    pub fn set_status(&mut self, status: ItemStatus) {
+        self.set_finished(status == ItemStatus::Done);
+        self.status = status;
+    }
 
-        let mut output: String = String::new();
+    fn title_prefix(&self, is_marked: bool) -> String {
+        let mut title = String::from(" ");
 
-        if offset.whole_weeks().abs() > 0 {
-            week = second / 604800;
-            second -= week * 604800;
-            output.push_str(&week.to_string());
-            output.push_str(" week, ");
+        if self.is_selected {
+            title.push_str("> ");
         }
 
-        if offset.whole_days().abs() > 0 {
-            day = second / 86400;
-            second -= day * 86400;
-            output.push_str(&day.to_string());
-            output.push_str(" day, ");
+        if is_marked {
+            title.push_str("* ");
         }
 
-        if offset.whole_hours().abs() > 0 {
-            hour = second / 3600;
-            second -= hour * 3600;
-            output.push_str(&hour.to_string());
-            output.push_str(" hour, ");
+        title.push_str("Status: ");
+        title
+    }
+
+    // Column of the `[ ]` status mark, counted from the left edge of the item.
+    pub fn mark_offset(&self, is_marked: bool) -> u16 {
+        1 + self.title_prefix(is_marked).chars().count() as u16
+    }
+
+    fn get_time_offset(&mut self, countdown: Countdown) -> String {
+        let offset = (self.expire_datetime - Local::now()).whole_seconds();
+
+        if offset < 0 {
+            self.is_late = true;
         }
 
-        if offset.whole_minutes().abs() > 0 {
-            minute = second / 60;
-            second -= minute * 60;
-            output.push_str(&minute.to_string());
-            output.push_str(" minute, ");
+        let date = self.expire_datetime.format("%Y-%m-%d %H:%M").to_string();
+        let units = split_units(offset.abs());
+
+        let relative = match countdown {
+            Countdown::Absolute => return format!("due {}", date),
+            // Less than a second either way has nothing to count.
+            _ if offset == 0 => String::from("due now"),
+            Countdown::Verbose => units
+                .iter()
+                .filter(|(amount, _)| *amount > 0)
+                .map(|&(amount, unit)| plural(amount, UNIT_NAMES[unit]))
+                .collect::<Vec<_>>()
+                .join(", "),
+            Countdown::Compact => units
+                .iter()
+                .skip_while(|(amount, _)| *amount == 0)
+                .take(2)
+                .filter(|(amount, _)| *amount > 0)
+                .map(|&(amount, unit)| format!("{}{}", amount, UNIT_SUFFIXES[unit]))
+                .collect::<String>(),
+            Countdown::Rounded | Countdown::Both => units
+                .iter()
+                .find(|(amount, _)| *amount > 0)
+                .map(|&(amount, unit)| plural(amount, UNIT_NAMES[unit]))
+                .unwrap_or_default(),
+        };
+
+        // Every relative format reads "overdue by ..." once the deadline has
+        // passed, and "... left" or "in ..." before it.
+        let relative = if offset == 0 {
+            relative
+        } else if offset < 0 {
+            format!("overdue by {}", relative)
+        } else {
+            match countdown {
+                Countdown::Rounded | Countdown::Both => format!("in {}", relative),
+                _ => format!("{} left", relative),
+            }
+        };
+
+        if countdown == Countdown::Both {
+            format!("{} ({})", relative, date)
+        } else {
+            relative
         }
+    }
+}
+
+const UNIT_NAMES: [&str; 5] = ["week", "day", "hour", "minute", "second"];
+const UNIT_SUFFIXES: [&str; 5] = ["w", "d", "h", "m", "s"];
+
+// Splits a number of seconds into weeks, days, hours, minutes and seconds,
+// paired with their index into UNIT_NAMES.
+fn split_units(mut seconds: i64) -> [(i64, usize); 5] {
+    let mut units = [(0, 0); 5];
+
+    for (index, &size) in [604800, 86400, 3600, 60, 1].iter().enumerate() {
+        units[index] = (seconds / size, index);
+        seconds %= size;
+    }
 
-        output.push_str(&second.to_string());
-        output.push_str(" second ");
+    units
+}
 
-        output
+fn plural(amount: i64, unit: &str) -> String {
+    if amount == 1 {
+        format!("{} {}", amount, unit)
+    } else {
+        format!("{} {}s", amount, unit)
     }
 }
 
-pub struct ItemWidget {}
+pub struct ItemWidget<'a> {
+    pub highlight: &'a str,
+    pub is_marked: bool,
+    pub countdown: Countdown,
+}
 
-impl StatefulWidget for ItemWidget {
+impl<'a> StatefulWidget for ItemWidget<'a> {
     type State = ItemState;
 
     fn render(self, mut area: Rect, buf: &mut Buffer, state: &mut Self::State) {
@@ -156,60 +374,93 @@ impl StatefulWidget for ItemWidget {
             "x"
         };
 
+        let mut title = state.title_prefix(self.is_marked);
+        title.push_str(&format!("[{}] ", mark));
+
+        if let ItemStatus::InProgress | ItemStatus::Blocked = state.status {
+            title.push_str(&format!("| {} ", state.status.title()));
+        }
+
+        if state.priority > 0 {
+            title.push_str(&format!("| Priority: {} ", PRIORITY_NAMES[state.priority]));
+        }
+
+        if state.has_expire_datetime {
+            title.push_str(&format!("| {} ", state.get_time_offset(self.countdown)));
+        }
+
+        if let Some(until) = state.snoozed_until.filter(|_| state.is_snoozed()) {
+            title.push_str(&format!(
+                "| Snoozed until {} ",
+                until.format("%Y-%m-%d %H:%M")
+            ));
+        }
+
+        if !state.deferrals.is_empty() {
+            title.push_str(&format!("| Deferred {}x ", state.deferrals.len()));
+        }
+
         Block::default()
             .title(Span::styled(
-                if state.has_expire_datetime {
-                    if state.is_selected {
-                        format!(
-                            " > Status: [{}] | Time Left: {} ",
-                            mark,
-                            state.get_time_offset()
-                        )
-                    } else {
-                        format!(
-                            " Status: [{}] | Time Left: {} ",
-                            mark,
-                            state.get_time_offset()
-                        )
-                    }
-                } else {
-                    if state.is_selected {
-                        format!(" > Status: [{}] ", mark)
-                    } else {
-                        format!(" Status: [{}] ", mark)
-                    }
-                },
+                title,
                 if state.is_finished {
-                    style().fg(THEME.finished)
+                    style().fg(theme().finished)
                 } else if state.is_late {
-                    style().fg(THEME.loss)
+                    style().fg(theme().loss)
                 } else {
-                    style().fg(THEME.text_normal)
+                    style().fg(theme().text_normal)
                 },
             ))
             .borders(Borders::ALL)
-            .border_style(if state.is_finished {
-                style().fg(THEME.finished)
+            .border_style(if self.is_marked {
+                style().fg(theme().focused)
+            } else if state.is_finished {
+                style().fg(theme().finished)
             } else if state.is_late {
-                style().fg(THEME.loss)
+                style().fg(theme().loss)
             } else {
-                style().fg(THEME.border_secondary)
+                style().fg(theme().border_secondary)
             })
             .render(area, buf);
         area = add_padding(area, 1, PaddingDirection::Top);
         area = add_padding(area, 1, PaddingDirection::Left);
         area = add_padding(area, 1, PaddingDirection::Right);
 
-        let text = vec![Span::styled(
-            format!(" Objective: {} ", state.text),
-            if state.is_finished {
-                style().fg(THEME.finished)
-            } else if state.is_late {
-                style().fg(THEME.loss)
-            } else {
-                style().fg(THEME.text_normal)
-            },
-        )];
+        let text_style = if state.is_finished {
+            style().fg(theme().finished)
+        } else if state.is_late {
+            style().fg(theme().loss)
+        } else {
+            style().fg(theme().text_normal)
+        };
+
+        let mut text = vec![Span::styled(" Objective: ", text_style)];
+        text.extend(highlight(&state.text, self.highlight, text_style));
+
+        for tag in state.tags.iter() {
+            text.push(Span::styled(" #", style().fg(theme().text_secondary)));
+            text.extend(highlight(
+                tag,
+                self.highlight,
+                style().fg(theme().text_secondary),
+            ));
+        }
+
+        if let Some(&(start, _)) = find_matches(&state.notes, self.highlight).first() {
+            let notes: String = state
+                .notes
+                .chars()
+                .skip(start.saturating_sub(NOTES_CONTEXT))
+                .map(|c| if c == '\n' { ' ' } else { c })
+                .collect();
+
+            text.push(Span::styled(" ~ ", style().fg(theme().unfocused)));
+            text.extend(highlight(
+                &notes,
+                self.highlight,
+                style().fg(theme().unfocused),
+            ));
+        }
 
         Paragraph::new(Spans::from(text))
             .style(style())
@@ -217,3 +468,36 @@ impl StatefulWidget for ItemWidget {
             .render(area, buf);
     }
 }
+
+const NOTES_CONTEXT: usize = 10;
+
+fn highlight(text: &str, query: &str, base_style: Style) -> Vec<Span<'static>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut spans = Vec::new();
+    let mut last = 0;
+
+    for (start, end) in find_matches(text, query) {
+        if start > last {
+            spans.push(Span::styled(
+                chars[last..start].iter().collect::<String>(),
+                base_style,
+            ));
+        }
+        spans.push(Span::styled(
+            chars[start..end].iter().collect::<String>(),
+            base_style
+                .patch(highlight_style())
+                .add_modifier(Modifier::BOLD),
+        ));
+        last = end;
+    }
+
+    if last < chars.len() {
+        spans.push(Span::styled(
+            chars[last..].iter().collect::<String>(),
+            base_style,
+        ));
+    }
+
+    spans
+}
@@ -4,4 +4,7 @@ mod item;
 
 pub use self::add_item::{AddItemState, AddItemWidget};
 pub use self::edit_item::{EditItemState, EditItemWidget};
-pub use self::item::{ItemState, ItemWidget};
+pub use self::item::{
+    parse_datetime, parse_snooze, ItemState, ItemStatus, ItemWidget, DATETIME_FORMAT,
+    PRIORITY_NAMES,
+};
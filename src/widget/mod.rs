@@ -1,12 +1,39 @@
+mod agenda;
+mod archive;
+mod board;
+mod calendar;
+mod command_line;
+mod confirm;
+mod dashboard;
+mod detail;
 mod help;
+mod input;
 mod item;
+mod palette;
+mod prompt;
+mod search;
+mod trash;
 mod workspace;
 
-pub use self::help::{HelpItemWidget, HelpWorkspaceWidget, HELP_HEIGHT, HELP_WIDTH};
+pub use self::agenda::{agenda_entries, AgendaState, AgendaWidget};
+pub use self::archive::{ArchiveState, ArchiveWidget, ArchivedItem};
+pub use self::board::{board_columns, wip_limit, BoardState, BoardWidget};
+pub use self::calendar::{items_due_on, CalendarState, CalendarWidget};
+pub use self::command_line::{CommandLineState, CommandLineWidget};
+pub use self::confirm::{ConfirmAction, ConfirmState, ConfirmWidget};
+pub use self::dashboard::DashboardWidget;
+pub use self::detail::DetailWidget;
+pub use self::help::{HelpPage, HelpState, HelpWidget, HELP_WIDTH};
+pub use self::input::InputState;
 pub use self::item::{
-    AddItemState, AddItemWidget, EditItemState, EditItemWidget, ItemState, ItemWidget,
+    parse_datetime, parse_snooze, AddItemState, AddItemWidget, EditItemState, EditItemWidget,
+    ItemState, ItemStatus, ItemWidget, DATETIME_FORMAT, PRIORITY_NAMES,
 };
+pub use self::palette::{PaletteState, PaletteWidget};
+pub use self::prompt::{PromptAction, PromptState, PromptWidget};
+pub use self::search::{find_matches, SearchState, SearchWidget};
+pub use self::trash::{TrashEntry, TrashState, TrashWidget, TrashedItem, TrashedWorkspace};
 pub use self::workspace::{
-    AddWorkspaceState, AddWorkspaceWidget, EditWorkspaceState, EditWorkspaceWidget, WorkspaceState,
-    WorkspaceWidget,
+    AddWorkspaceState, AddWorkspaceWidget, EditWorkspaceState, EditWorkspaceWidget, SortMode,
+    WorkspaceState, WorkspaceWidget,
 };
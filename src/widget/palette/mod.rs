@@ -0,0 +1,3 @@
+mod palette;
+
+pub use self::palette::{PaletteState, PaletteWidget};
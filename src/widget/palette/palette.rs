@@ -0,0 +1,205 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::Modifier;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Clear};
+use tui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::draw::{add_padding, PaddingDirection};
+use crate::keymap::{Action, KeymapMode};
+use crate::theme::{highlight_style, style, theme};
+use crate::widget::{InputState, HELP_WIDTH};
+use crate::KEYMAP;
+
+// Scores `text` against the characters of `query` typed in order, lower
+// being better. Returns `None` when some character is missing.
+fn fuzzy_score(text: &str, query: &str) -> Option<usize> {
+    let mut chars = text.chars().flat_map(char::to_lowercase).enumerate();
+    let mut score = 0;
+    let mut last = None;
+
+    for c in query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+    {
+        let (position, _) = chars.find(|&(_, text_c)| text_c == c)?;
+
+        score += match last {
+            Some(last) => position - last - 1,
+            None => position,
+        };
+        last = Some(position);
+    }
+
+    Some(score)
+}
+
+pub struct PaletteState {
+    pub input: InputState,
+    pub mode: KeymapMode,
+    pub selected: usize,
+}
+
+impl PaletteState {
+    pub fn new() -> PaletteState {
+        PaletteState {
+            input: InputState::new(),
+            mode: KeymapMode::Item,
+            selected: 0,
+        }
+    }
+
+    pub fn open(&mut self, mode: KeymapMode) {
+        self.input.clear();
+        self.mode = mode;
+        self.selected = 0;
+    }
+
+    pub fn handle_key(&mut self, keycode: KeyCode, modifiers: KeyModifiers) -> bool {
+        let query = self.input.text().to_string();
+        let is_handled = self.input.handle_key(keycode, modifiers);
+
+        if self.input.text() != query {
+            self.selected = 0;
+        }
+
+        is_handled
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        self.input.insert_str(text);
+        self.selected = 0;
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.entries().len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn selected_action(&self) -> Option<Action> {
+        self.entries().get(self.selected).map(|&(action, _)| action)
+    }
+
+    // Actions of the mode matching the query, best first, with their keys.
+    fn entries(&self) -> Vec<(Action, String)> {
+        let query = self.input.text();
+
+        let mut entries: Vec<(usize, Action, String)> = KEYMAP
+            .actions(self.mode)
+            .into_iter()
+            .filter(|&(action, _)| action != Action::Palette)
+            .filter_map(|(action, keys)| {
+                let name = action.name().replace('_', " ");
+                let score = fuzzy_score(action.description(), query)
+                    .into_iter()
+                    .chain(fuzzy_score(&name, query))
+                    .min()?;
+                let keys = keys
+                    .iter()
+                    .map(|key| format!("<{}>", key.name()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                Some((score, action, keys))
+            })
+            .collect();
+
+        entries.sort_by_key(|&(score, _, _)| score);
+
+        entries
+            .into_iter()
+            .map(|(_, action, keys)| (action, keys))
+            .collect()
+    }
+
+    pub fn get_rect(&self, area: Rect) -> Rect {
+        let width = (HELP_WIDTH as u16).min(area.width);
+        let height = (area.height * 2 / 3).max(8).min(area.height);
+
+        Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        }
+    }
+}
+
+pub struct PaletteWidget {}
+
+impl StatefulWidget for PaletteWidget {
+    type State = PaletteState;
+
+    fn render(self, mut area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Clear.render(area, buf);
+
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style().fg(theme().border_primary))
+            .title(Span::styled(
+                KEYMAP.title(
+                    "Command Palette",
+                    KeymapMode::Palette,
+                    &[
+                        (&[Action::ScrollUp, Action::ScrollDown], "select"),
+                        (&[Action::Submit], "run"),
+                    ],
+                ),
+                style().fg(theme().text_normal),
+            ))
+            .style(style())
+            .render(area, buf);
+        area = add_padding(area, 1, PaddingDirection::All);
+        area = add_padding(area, 1, PaddingDirection::Left);
+        area = add_padding(area, 1, PaddingDirection::Right);
+
+        let mut spans = vec![Span::styled("> ", style().fg(theme().text_normal))];
+        spans.extend(
+            state.input.spans(
+                area.width.saturating_sub(2),
+                style()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(theme().text_secondary),
+            ),
+        );
+        Paragraph::new(Spans::from(spans)).render(area, buf);
+
+        let list_area = add_padding(area, 2, PaddingDirection::Top);
+        let height = list_area.height as usize;
+        let width = list_area.width as usize;
+
+        let entries = state.entries();
+        state.selected = state.selected.min(entries.len().saturating_sub(1));
+        let offset = (state.selected + 1).saturating_sub(height);
+
+        let lines: Vec<Spans> = entries
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(height)
+            .map(|(index, (action, keys))| {
+                let description = action.description();
+                let gap = width.saturating_sub(description.chars().count() + keys.chars().count());
+                let line = format!("{}{:gap$}{}", description, "", keys, gap = gap);
+
+                Spans::from(Span::styled(
+                    line,
+                    if index == state.selected {
+                        highlight_style()
+                    } else {
+                        style().fg(theme().text_normal)
+                    },
+                ))
+            })
+            .collect();
+
+        Paragraph::new(lines).render(list_area, buf);
+    }
+}
@@ -0,0 +1,3 @@
+mod prompt;
+
+pub use self::prompt::{PromptAction, PromptState, PromptWidget};
@@ -0,0 +1,95 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use tui::buffer::Buffer;
+use tui::layout::{Alignment, Rect};
+use tui::style::Modifier;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders};
+use tui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::theme::{style, theme};
+use crate::widget::InputState;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum PromptAction {
+    SetDeadline,
+    AddTag,
+    SetPriority,
+    SetReminders,
+    Snooze,
+    SnoozeAndDefer,
+}
+
+impl PromptAction {
+    fn title(self) -> &'static str {
+        match self {
+            PromptAction::SetDeadline => " Set Deadline ",
+            PromptAction::AddTag => " Add Tag ",
+            PromptAction::SetPriority => " Set Priority (none, low, medium, high) ",
+            PromptAction::SetReminders => " Set Reminders (e.g. 1d 1h 0, none, empty for default) ",
+            PromptAction::Snooze => " Snooze Until (+1h, tomorrow, monday, date, empty to wake) ",
+            PromptAction::SnoozeAndDefer => {
+                " Snooze And Push Deadline Until (+1h, tomorrow, monday) "
+            }
+        }
+    }
+}
+
+pub struct PromptState {
+    pub input: InputState,
+    pub action: Option<PromptAction>,
+}
+
+impl PromptState {
+    pub fn new() -> PromptState {
+        PromptState {
+            input: InputState::new(),
+            action: None,
+        }
+    }
+
+    pub fn open(&mut self, action: PromptAction) {
+        self.reset();
+        self.action = Some(action);
+    }
+
+    pub fn handle_key(&mut self, keycode: KeyCode, modifiers: KeyModifiers) -> bool {
+        self.input.handle_key(keycode, modifiers)
+    }
+
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.action = None;
+    }
+}
+
+pub struct PromptWidget {}
+
+impl StatefulWidget for PromptWidget {
+    type State = PromptState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let mut spans = vec![Span::styled("> ", style().fg(theme().text_normal))];
+        spans.extend(
+            state.input.spans(
+                area.width.saturating_sub(4),
+                style()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(theme().text_secondary),
+            ),
+        );
+
+        Paragraph::new(Spans::from(spans))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(style().fg(theme().border_primary))
+                    .title(Span::styled(
+                        state.action.map(PromptAction::title).unwrap_or(" "),
+                        style().fg(theme().text_normal),
+                    )),
+            )
+            .style(style())
+            .alignment(Alignment::Left)
+            .render(area, buf);
+    }
+}
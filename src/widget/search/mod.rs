@@ -0,0 +1,3 @@
+mod search;
+
+pub use self::search::{find_matches, SearchState, SearchWidget};
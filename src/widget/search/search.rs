@@ -0,0 +1,146 @@
+use crossterm::event::{KeyCode, KeyModifiers};
+use tui::buffer::Buffer;
+use tui::layout::{Alignment, Rect};
+use tui::style::Modifier;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders};
+use tui::widgets::{Paragraph, StatefulWidget, Widget};
+
+use crate::keymap::{Action, KeymapMode};
+use crate::theme::{style, theme};
+use crate::widget::{InputState, ItemState};
+use crate::KEYMAP;
+
+pub struct SearchState {
+    pub input: InputState,
+    pub all_workspaces: bool,
+    pub num_of_match: usize,
+}
+
+impl SearchState {
+    pub fn new() -> SearchState {
+        SearchState {
+            input: InputState::new(),
+            all_workspaces: false,
+            num_of_match: 0,
+        }
+    }
+
+    pub fn handle_key(&mut self, keycode: KeyCode, modifiers: KeyModifiers) -> bool {
+        self.input.handle_key(keycode, modifiers)
+    }
+
+    pub fn reset(&mut self) {
+        self.input.clear();
+        self.all_workspaces = false;
+        self.num_of_match = 0;
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.input.is_empty()
+    }
+
+    // A query starting with `#` only matches tags.
+    pub fn matches(&self, item: &ItemState) -> bool {
+        let query = self.input.text();
+
+        if let Some(tag) = query.strip_prefix('#').filter(|tag| !tag.is_empty()) {
+            return item
+                .tags
+                .iter()
+                .any(|item_tag| !find_matches(item_tag, tag).is_empty());
+        }
+
+        !self.is_active()
+            || !find_matches(&item.text, query).is_empty()
+            || !find_matches(&item.notes, query).is_empty()
+            || item
+                .tags
+                .iter()
+                .any(|tag| !find_matches(tag, query).is_empty())
+    }
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+// Returns the case-insensitive matches of `query` in `text` as `(start, end)`
+// character ranges.
+pub fn find_matches(text: &str, query: &str) -> Vec<(usize, usize)> {
+    let text: Vec<char> = text.chars().map(fold).collect();
+    let query: Vec<char> = query.chars().map(fold).collect();
+
+    let mut matches = Vec::new();
+
+    if query.is_empty() || query.len() > text.len() {
+        return matches;
+    }
+
+    let mut start = 0;
+    while start + query.len() <= text.len() {
+        if text[start..start + query.len()] == query[..] {
+            matches.push((start, start + query.len()));
+            start += query.len();
+        } else {
+            start += 1;
+        }
+    }
+
+    matches
+}
+
+pub struct SearchWidget {}
+
+impl StatefulWidget for SearchWidget {
+    type State = SearchState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let mut spans = vec![Span::styled("/ ", style().fg(theme().text_normal))];
+        spans.extend(
+            state.input.spans(
+                area.width.saturating_sub(4),
+                style()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(theme().text_secondary),
+            ),
+        );
+
+        let toggle = KEYMAP
+            .keys(KeymapMode::Search, Action::ToggleAllWorkspaces)
+            .first()
+            .map(|key| {
+                format!(
+                    ", <{}>: {}",
+                    key.name(),
+                    if state.all_workspaces {
+                        "current workspace"
+                    } else {
+                        "all workspaces"
+                    }
+                )
+            })
+            .unwrap_or_default();
+        let title = format!(
+            " Search{} ({} matches{}) ",
+            if state.all_workspaces {
+                " All Workspaces"
+            } else {
+                ""
+            },
+            state.num_of_match,
+            toggle,
+        );
+
+        Paragraph::new(Spans::from(spans))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(style().fg(theme().border_primary))
+                    .title(Span::styled(title, style().fg(theme().text_normal))),
+            )
+            .style(style())
+            .alignment(Alignment::Left)
+            .render(area, buf);
+    }
+}
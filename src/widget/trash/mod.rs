@@ -0,0 +1,3 @@
+mod trash;
+
+pub use self::trash::{TrashEntry, TrashState, TrashWidget, TrashedItem, TrashedWorkspace};
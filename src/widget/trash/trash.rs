@@ -0,0 +1,191 @@
+use chrono::{DateTime, Local};
+use tui::buffer::Buffer;
+use tui::layout::Rect;
+use tui::style::Modifier;
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, Paragraph, StatefulWidget, Widget};
+use unicode_width::UnicodeWidthStr;
+
+use crate::draw::{add_padding, PaddingDirection};
+use crate::keymap::{Action, KeymapMode};
+use crate::theme::{highlight_style, style, theme};
+use crate::widget::{ItemState, SortMode};
+use crate::KEYMAP;
+
+// A deleted item, with the title of the workspace to restore it to.
+pub struct TrashedItem {
+    pub workspace: String,
+    pub deleted: DateTime<Local>,
+    pub item: ItemState,
+}
+
+// A deleted workspace together with the items it had.
+pub struct TrashedWorkspace {
+    pub title: String,
+    pub sort: SortMode,
+    pub deleted: DateTime<Local>,
+    pub items: Vec<ItemState>,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum TrashEntry {
+    Item(usize),
+    Workspace(usize),
+}
+
+pub struct TrashState {
+    pub selected: usize,
+    offset: usize,
+}
+
+impl TrashState {
+    pub fn new() -> TrashState {
+        TrashState {
+            selected: 0,
+            offset: 0,
+        }
+    }
+
+    // Deleted items and workspaces, the most recently deleted first.
+    pub fn entries(items: &[TrashedItem], workspaces: &[TrashedWorkspace]) -> Vec<TrashEntry> {
+        let mut entries: Vec<(DateTime<Local>, TrashEntry)> = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (item.deleted, TrashEntry::Item(index)))
+            .chain(
+                workspaces
+                    .iter()
+                    .enumerate()
+                    .map(|(index, workspace)| (workspace.deleted, TrashEntry::Workspace(index))),
+            )
+            .collect();
+
+        entries.sort_by(|a, b| b.0.cmp(&a.0));
+        entries.into_iter().map(|(_, entry)| entry).collect()
+    }
+}
+
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+const MAX_WORKSPACE_WIDTH: usize = 20;
+
+pub struct TrashWidget<'a> {
+    pub items: &'a [TrashedItem],
+    pub workspaces: &'a [TrashedWorkspace],
+}
+
+impl<'a> StatefulWidget for TrashWidget<'a> {
+    type State = TrashState;
+
+    fn render(self, mut area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(style().fg(theme().border_primary))
+            .title(Span::styled(
+                KEYMAP.title(
+                    "Trash",
+                    KeymapMode::Trash,
+                    &[(&[Action::Restore], "restore"), (&[Action::Close], "back")],
+                ),
+                style().fg(theme().text_normal),
+            ))
+            .style(style())
+            .render(area, buf);
+        area = add_padding(area, 1, PaddingDirection::All);
+        area = add_padding(area, 1, PaddingDirection::Left);
+        area = add_padding(area, 1, PaddingDirection::Right);
+
+        let entries = TrashState::entries(self.items, self.workspaces);
+
+        if entries.is_empty() {
+            Paragraph::new(Span::styled(
+                "Trash is empty",
+                style().fg(theme().unfocused),
+            ))
+            .render(area, buf);
+            return;
+        }
+
+        state.selected = state.selected.min(entries.len() - 1);
+
+        let height = area.height as usize;
+        if state.selected < state.offset {
+            state.offset = state.selected;
+        } else if state.selected >= state.offset + height {
+            state.offset = state.selected + 1 - height;
+        }
+
+        let workspace_width = self
+            .items
+            .iter()
+            .map(|item| item.workspace.width())
+            .max()
+            .unwrap_or(0)
+            .min(MAX_WORKSPACE_WIDTH);
+        let width = area.width as usize;
+
+        let lines: Vec<Spans> = entries
+            .iter()
+            .enumerate()
+            .skip(state.offset)
+            .take(height)
+            .map(|(position, &entry)| {
+                let (left, workspace, deleted, entry_style) = match entry {
+                    TrashEntry::Item(index) => {
+                        let trashed = &self.items[index];
+
+                        (
+                            format!(
+                                "[{}] {}",
+                                if trashed.item.is_finished { "✓" } else { "-" },
+                                trashed.item.text
+                            ),
+                            trashed
+                                .workspace
+                                .chars()
+                                .take(MAX_WORKSPACE_WIDTH)
+                                .collect(),
+                            trashed.deleted,
+                            style().fg(theme().text_normal),
+                        )
+                    }
+                    TrashEntry::Workspace(index) => {
+                        let trashed = &self.workspaces[index];
+
+                        (
+                            format!(
+                                "Workspace '{}' ({} item{})",
+                                trashed.title,
+                                trashed.items.len(),
+                                if trashed.items.len() == 1 { "" } else { "s" }
+                            ),
+                            String::new(),
+                            trashed.deleted,
+                            style()
+                                .fg(theme().text_primary)
+                                .add_modifier(Modifier::BOLD),
+                        )
+                    }
+                };
+
+                let right = format!(
+                    "  {:<workspace_width$}  deleted {}",
+                    workspace,
+                    deleted.format(DATE_FORMAT),
+                    workspace_width = workspace_width
+                );
+                let gap = width.saturating_sub(left.width() + right.width());
+
+                Spans::from(Span::styled(
+                    format!("{}{:gap$}{}", left, "", right, gap = gap),
+                    if position == state.selected {
+                        highlight_style()
+                    } else {
+                        entry_style
+                    },
+                ))
+            })
+            .collect();
+
+        Paragraph::new(lines).render(area, buf);
+    }
+}
@@ -1,15 +1,16 @@
+use crossterm::event::{KeyCode, KeyModifiers};
 use tui::buffer::Buffer;
 use tui::layout::{Alignment, Rect};
 use tui::style::Modifier;
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders};
-use tui::widgets::{Paragraph, StatefulWidget, Widget, Wrap};
+use tui::widgets::{Paragraph, StatefulWidget, Widget};
 
-use crate::theme::style;
-use crate::THEME;
+use crate::theme::{style, theme};
+use crate::widget::InputState;
 
 pub struct EditWorkspaceState {
-    pub input_string: String,
+    pub input: InputState,
     has_input: bool,
     error_msg: Option<String>,
 }
@@ -17,29 +18,38 @@ pub struct EditWorkspaceState {
 impl EditWorkspaceState {
     pub fn new() -> EditWorkspaceState {
         EditWorkspaceState {
-            input_string: String::new(),
+            input: InputState::new(),
             has_input: false,
-            error_msg: Some(String::new()),
+            error_msg: None,
         }
     }
 
-    pub fn add_char(&mut self, c: char) {
-        self.input_string.push(c);
-        self.has_input = true;
+    pub fn handle_key(&mut self, keycode: KeyCode, modifiers: KeyModifiers) -> bool {
+        let handled = self.input.handle_key(keycode, modifiers);
+        self.has_input |= handled;
+        handled
     }
 
-    pub fn del_char(&mut self) {
-        self.input_string.pop();
+    pub fn paste(&mut self, text: &str) {
+        self.input.insert_str(text);
+        self.has_input = true;
     }
 
     pub fn reset(&mut self) {
-        self.input_string.drain(..);
+        self.input.clear();
         self.has_input = false;
         self.error_msg = None;
     }
 
-    pub fn enter(&mut self, slot: usize, num_of_item: usize) -> super::WorkspaceState {
-        super::WorkspaceState::new(slot, self.input_string.clone(), num_of_item, true)
+    pub fn open(&mut self, workspace: &super::WorkspaceState) {
+        self.reset();
+        self.input.set_text(workspace.title.clone());
+    }
+
+    pub fn enter(&mut self, workspace: &mut super::WorkspaceState) {
+        if !self.input.is_empty() {
+            workspace.title = self.input.text().to_string();
+        }
     }
 }
 
@@ -51,36 +61,36 @@ impl StatefulWidget for EditWorkspaceWidget {
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
         let spans = if !state.has_input && state.error_msg.is_some() {
             Spans::from(vec![
-                Span::styled("> ", style().fg(THEME.text_normal)),
+                Span::styled("> ", style().fg(theme().text_normal)),
                 Span::styled(
                     state.error_msg.as_ref().unwrap(),
-                    style().add_modifier(Modifier::BOLD).fg(THEME.loss),
+                    style().add_modifier(Modifier::BOLD).fg(theme().loss),
                 ),
             ])
         } else {
-            Spans::from(vec![
-                Span::styled("> ", style().fg(THEME.text_normal)),
-                Span::styled(
-                    &state.input_string,
+            let mut spans = vec![Span::styled("> ", style().fg(theme().text_normal))];
+            spans.extend(
+                state.input.spans(
+                    area.width.saturating_sub(4),
                     style()
                         .add_modifier(Modifier::BOLD)
-                        .fg(THEME.text_secondary),
+                        .fg(theme().text_secondary),
                 ),
-            ])
+            );
+            Spans::from(spans)
         };
         Paragraph::new(spans)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(style().fg(THEME.border_primary))
+                    .border_style(style().fg(theme().border_primary))
                     .title(Span::styled(
                         " Rename Workspace ",
-                        style().fg(THEME.text_normal),
+                        style().fg(theme().text_normal),
                     )),
             )
             .style(style())
             .alignment(Alignment::Left)
-            .wrap(Wrap { trim: true })
             .render(area, buf);
     }
 }
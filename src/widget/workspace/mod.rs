@@ -4,4 +4,4 @@ mod workspace;
 
 pub use self::add_workspace::{AddWorkspaceState, AddWorkspaceWidget};
 pub use self::edit_workspace::{EditWorkspaceState, EditWorkspaceWidget};
-pub use self::workspace::{WorkspaceState, WorkspaceWidget};
+pub use self::workspace::{SortMode, WorkspaceState, WorkspaceWidget};
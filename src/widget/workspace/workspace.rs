@@ -2,15 +2,60 @@ use tui::buffer::Buffer;
 use tui::layout::Rect;
 use tui::text::Span;
 use tui::widgets::{Block, StatefulWidget, Widget};
+use unicode_width::UnicodeWidthStr;
 
-use crate::theme::style;
-use crate::THEME;
+use crate::theme::{highlight_style, style, theme};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum SortMode {
+    Manual,
+    Deadline,
+    Priority,
+    Status,
+    Created,
+    Alphabetical,
+}
+
+impl SortMode {
+    pub const ALL: [SortMode; 6] = [
+        SortMode::Manual,
+        SortMode::Deadline,
+        SortMode::Priority,
+        SortMode::Status,
+        SortMode::Created,
+        SortMode::Alphabetical,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SortMode::Manual => "manual",
+            SortMode::Deadline => "deadline",
+            SortMode::Priority => "priority",
+            SortMode::Status => "status",
+            SortMode::Created => "created",
+            SortMode::Alphabetical => "alphabetical",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<SortMode> {
+        SortMode::ALL
+            .iter()
+            .copied()
+            .find(|sort| sort.name() == name)
+    }
+
+    pub fn next(self) -> SortMode {
+        let index = SortMode::ALL.iter().position(|&sort| sort == self).unwrap();
+        SortMode::ALL[(index + 1) % SortMode::ALL.len()]
+    }
+}
 
 pub struct WorkspaceState {
     pub slot: usize,
     pub title: String,
     pub num_of_item: usize,
     pub is_selected: bool,
+    pub sort: SortMode,
 }
 
 impl WorkspaceState {
@@ -25,23 +70,32 @@ impl WorkspaceState {
             title,
             num_of_item,
             is_selected,
+            sort: SortMode::Manual,
         }
     }
 }
 
-pub struct WorkspaceWidget {}
+// Pending and late counts are shown at the right edge of the row.
+pub struct WorkspaceWidget {
+    pub num_of_pending: usize,
+    pub num_of_late: usize,
+}
 
 impl StatefulWidget for WorkspaceWidget {
     type State = WorkspaceState;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let title = format!("({}) + {}", state.slot, state.title);
+        let counts = format!(" {} - {} x ", self.num_of_pending, self.num_of_late);
+        let gap = (area.width as usize).saturating_sub(title.width() + counts.width());
+
         Block::default()
             .title(Span::styled(
-                format!("({}) + {: <1000}", state.slot, state.title),
+                format!("{}{:gap$}{}", title, "", counts, gap = gap),
                 if state.is_selected {
-                    style().fg(THEME.text_dark).bg(THEME.text_primary)
+                    highlight_style()
                 } else {
-                    style().fg(THEME.text_normal).bg(THEME.background)
+                    style().fg(theme().text_normal).bg(theme().background)
                 },
             ))
             .render(area, buf);